@@ -0,0 +1,257 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A camera over the game grid. Cells sit in world coordinates, one unit per cell,
+// and the camera maps them into the window pixels of the game area and back, so
+// drawing and mouse hit-testing always agree.
+
+//Import global parameters
+use params::{ SIZE_GRID_PIXELS };
+
+///Closest zoom, in pixels per cell
+pub const MAX_ZOOM: f32 = 40.0;
+
+///Farthest zoom, in pixels per cell
+pub const MIN_ZOOM: f32 = 1.0 / 64.0;
+
+///Cells of space left around a pattern when fitting it into view
+const FIT_MARGIN: f32 = 2.0;
+
+///Decides which part of the world is shown in the game area, and how big
+pub struct Camera {
+	///Window pixels the world is drawn in: (x, y, width, height)
+	viewport: (f32, f32, f32, f32),
+	///World coordinate shown at the top left corner of the viewport
+	origin: (f32, f32),
+	///Pixels per cell
+	zoom: f32,
+}
+
+impl Camera {
+	/// Generates a camera showing the top left of the world at the default grid size.
+	///
+	/// # Arguments
+	/// * 'viewport' - The window pixels to draw in, as (x, y, width, height).
+	pub fn new(viewport: (f32, f32, f32, f32)) -> Self {
+		Camera { viewport, origin: (0.0, 0.0), zoom: SIZE_GRID_PIXELS as f32 }
+	}
+
+	/// Returns the window pixels the world is drawn in, as (x, y, width, height)
+	pub fn viewport(&self) -> (f32, f32, f32, f32) {
+		self.viewport
+	}
+
+	/// Returns the number of pixels per cell
+	pub fn zoom(&self) -> f32 {
+		self.zoom
+	}
+
+	/// Checks if a window pixel is in the viewport.
+	///
+	/// # Arguments
+	/// * 'x & y' - The pixel.
+	pub fn contains(&self, x: f32, y: f32) -> bool {
+		let (vx, vy, vw, vh) = self.viewport;
+		x >= vx && y >= vy && x < vx + vw && y < vy + vh
+	}
+
+	/// Maps a world coordinate to the window pixel it is drawn at.
+	///
+	/// # Arguments
+	/// * 'x & y' - The world coordinate.
+	pub fn to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+		(self.viewport.0 + (x - self.origin.0) * self.zoom, self.viewport.1 + (y - self.origin.1) * self.zoom)
+	}
+
+	/// Maps a window pixel to the world coordinate drawn there.
+	///
+	/// # Arguments
+	/// * 'x & y' - The pixel.
+	pub fn to_world(&self, x: f32, y: f32) -> (f32, f32) {
+		(self.origin.0 + (x - self.viewport.0) / self.zoom, self.origin.1 + (y - self.viewport.1) / self.zoom)
+	}
+
+	/// Maps a window pixel to the cell drawn there.
+	///
+	/// # Arguments
+	/// * 'x & y' - The pixel.
+	///
+	/// # Return
+	/// * Option<(i32, i32)> - The (x, y) coordinate of the cell, None if the pixel is outside of the viewport.
+	pub fn cell_at(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+		if !self.contains(x as f32, y as f32) {
+			return None;
+		}
+		//Aim for the middle of the pixel
+		let (x, y) = self.to_world(x as f32 + 0.5, y as f32 + 0.5);
+		Some((x.floor() as i32, y.floor() as i32))
+	}
+
+	/// Returns the cells at least partly in view, as (x0, y0, x1, y1) with x1 and y1 excluded
+	pub fn visible(&self) -> (i32, i32, i32, i32) {
+		let (vx, vy, vw, vh) = self.viewport;
+		let start = self.to_world(vx, vy);
+		let end = self.to_world(vx + vw, vy + vh);
+		(start.0.floor() as i32, start.1.floor() as i32, end.0.ceil() as i32, end.1.ceil() as i32)
+	}
+
+	/// Cuts a rectangle of window pixels down to the part inside the viewport.
+	///
+	/// # Arguments
+	/// * 'rect' - The rectangle as (x, y, width, height).
+	///
+	/// # Return
+	/// * Option<(f32, f32, f32, f32)> - The part inside the viewport, None if there is none.
+	pub fn clip(&self, rect: (f32, f32, f32, f32)) -> Option<(f32, f32, f32, f32)> {
+		let (vx, vy, vw, vh) = self.viewport;
+		let x0 = rect.0.max(vx);
+		let y0 = rect.1.max(vy);
+		let x1 = (rect.0 + rect.2).min(vx + vw);
+		let y1 = (rect.1 + rect.3).min(vy + vh);
+		if x1 > x0 && y1 > y0 { Some((x0, y0, x1 - x0, y1 - y0)) } else { None }
+	}
+
+	/// Cuts a convex polygon of window pixels down to the part inside the viewport.
+	///
+	/// # Arguments
+	/// * 'points' - The corners of the polygon in order.
+	///
+	/// # Return
+	/// * Vec<(f32, f32)> - The corners of the part inside the viewport, fewer than 3 if there is none.
+	pub fn clip_polygon(&self, points: &[(f32, f32)]) -> Vec<(f32, f32)> {
+		let (vx, vy, vw, vh) = self.viewport;
+		let mut points = points.to_vec();
+		//Cut away everything past each edge of the viewport in turn
+		for edge in 0..4 {
+			let past = |point: (f32, f32)| match edge {
+				0 => point.0 - vx,
+				1 => vx + vw - point.0,
+				2 => point.1 - vy,
+				_ => vy + vh - point.1,
+			};
+			let cross = |a: (f32, f32), b: (f32, f32)| {
+				let t = past(a) / (past(a) - past(b));
+				(a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+			};
+			let mut clipped = Vec::new();
+			for i in 0..points.len() {
+				let (a, b) = (points[(i + points.len() - 1) % points.len()], points[i]);
+				match (past(a) >= 0.0, past(b) >= 0.0) {
+					(true, true) => clipped.push(b),
+					(true, false) => clipped.push(cross(a, b)),
+					(false, true) => {
+						clipped.push(cross(a, b));
+						clipped.push(b);
+					},
+					(false, false) => (),
+				}
+			}
+			points = clipped;
+		}
+		points
+	}
+
+	/// Moves the world across the viewport, as if it were dragged by the mouse.
+	///
+	/// # Arguments
+	/// * 'dx & dy' - Pixels to move the world by.
+	pub fn pan(&mut self, dx: f32, dy: f32) {
+		self.origin.0 -= dx / self.zoom;
+		self.origin.1 -= dy / self.zoom;
+	}
+
+	/// Zooms in or out, keeping the world coordinate under a pixel in place.
+	///
+	/// # Arguments
+	/// * 'x & y'  - The pixel to zoom around, usually the mouse.
+	/// * 'factor' - How much to zoom by, above 1 zooms in.
+	pub fn zoom_at(&mut self, x: f32, y: f32, factor: f32) {
+		let anchor = self.to_world(x, y);
+		self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+		self.origin = (anchor.0 - (x - self.viewport.0) / self.zoom, anchor.1 - (y - self.viewport.1) / self.zoom);
+	}
+
+	/// Moves the camera so a world coordinate is in the middle of the viewport.
+	///
+	/// # Arguments
+	/// * 'x & y' - The world coordinate.
+	pub fn center_on(&mut self, x: f32, y: f32) {
+		let (_, _, vw, vh) = self.viewport;
+		self.origin = (x - vw / 2.0 / self.zoom, y - vh / 2.0 / self.zoom);
+	}
+
+	/// Zooms and centers the camera so a block of cells fills the viewport.
+	///
+	/// # Arguments
+	/// * 'bounds' - The cells to show, as (x, y, width, height).
+	pub fn fit(&mut self, bounds: (i32, i32, i32, i32)) {
+		let (_, _, vw, vh) = self.viewport;
+		let (x, y, w, h) = (bounds.0 as f32, bounds.1 as f32, bounds.2 as f32, bounds.3 as f32);
+		let zoom = (vw / (w + 2.0 * FIT_MARGIN)).min(vh / (h + 2.0 * FIT_MARGIN));
+		self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+		self.center_on(x + w / 2.0, y + h / 2.0);
+	}
+}
+
+#[test]
+fn test_camera_transform() {
+	let mut camera = Camera::new((100.0, 50.0, 200.0, 100.0));
+	let zoom = SIZE_GRID_PIXELS as f32;
+	assert_eq!((100.0, 50.0), camera.to_screen(0.0, 0.0));
+	assert_eq!((100.0 + 3.0 * zoom, 50.0 + 2.0 * zoom), camera.to_screen(3.0, 2.0));
+	assert_eq!((3.0, 2.0), camera.to_world(100.0 + 3.0 * zoom, 50.0 + 2.0 * zoom));
+
+	//Pixels map to the cell under them, but only inside the viewport
+	assert_eq!(Some((0, 0)), camera.cell_at(100, 50));
+	assert_eq!(Some((1, 0)), camera.cell_at(100 + SIZE_GRID_PIXELS, 50 + SIZE_GRID_PIXELS - 1));
+	assert_eq!(None, camera.cell_at(99, 50));
+	assert_eq!(None, camera.cell_at(300, 50));
+
+	//Dragging the world right brings cells to the left into view
+	camera.pan(2.0 * zoom, 0.0);
+	assert_eq!(Some((-2, 0)), camera.cell_at(100, 50));
+	assert_eq!((-2, 0, 38, 20), camera.visible());
+
+	assert_eq!(Some((100.0, 140.0, 10.0, 10.0)), camera.clip((90.0, 140.0, 20.0, 20.0)));
+	assert_eq!(None, camera.clip((0.0, 0.0, 100.0, 50.0)));
+
+	//Polygons lose the corners outside the viewport
+	assert_eq!(vec![(110.0, 60.0), (120.0, 60.0), (110.0, 70.0)], camera.clip_polygon(&[(110.0, 60.0), (120.0, 60.0), (110.0, 70.0)]));
+	assert_eq!(vec![(100.0, 60.0), (110.0, 60.0), (110.0, 70.0), (100.0, 70.0)], camera.clip_polygon(&[(90.0, 60.0), (110.0, 60.0), (110.0, 70.0), (90.0, 70.0)]));
+	assert!(camera.clip_polygon(&[(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)]).is_empty());
+}
+
+#[test]
+fn test_camera_zoom_and_fit() {
+	let mut camera = Camera::new((0.0, 0.0, 200.0, 100.0));
+
+	//The cell under the mouse stays under the mouse
+	let before = camera.to_world(60.0, 30.0);
+	camera.zoom_at(60.0, 30.0, 2.0);
+	assert_eq!(2.0 * SIZE_GRID_PIXELS as f32, camera.zoom());
+	assert_eq!(before, camera.to_world(60.0, 30.0));
+
+	camera.zoom_at(0.0, 0.0, 1000.0);
+	assert_eq!(MAX_ZOOM, camera.zoom());
+	camera.zoom_at(0.0, 0.0, 0.0);
+	assert_eq!(MIN_ZOOM, camera.zoom());
+
+	//A 6x6 pattern with its margin is limited by the viewport height
+	camera.fit((10, 20, 6, 6));
+	assert_eq!(10.0, camera.zoom());
+	assert_eq!((100.0, 50.0), camera.to_screen(13.0, 23.0));
+
+	camera.center_on(0.0, 0.0);
+	assert_eq!((100.0, 50.0), camera.to_screen(0.0, 0.0));
+}
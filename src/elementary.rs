@@ -0,0 +1,271 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// One dimensional automata, drawn as a space-time diagram: each generation is a row of
+// cells, written below the row before it. Once the rows reach the bottom of the map,
+// the diagram scrolls up a row a generation.
+//
+// Wolfram's elementary rules are written W0 to W255, such as W30 and W110. A cell's next
+// state is a bit of the rule number, picked by reading the cell and the cells on either
+// side of it as a binary number, the left cell highest: bit 7 for three living cells,
+// bit 0 for three dead ones.
+//
+// Totalistic rules are written T and a code, such as T6, with R and a radius for wider
+// neighborhoods, such as T10R2. A cell's next state is bit n of the code, where n is the
+// number of living cells within the radius, the cell itself included.
+//
+// Cells past the ends of a row are dead, the cells at the other end (wrap), or the cells
+// reflected back in from the nearest end (mirror), as the boundary says.
+
+use std::fmt;
+
+use rule::{State, DEAD, ALIVE, MAX_RADIUS};
+
+///What the cells past the ends of a row are taken to be
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+	///Always dead
+	Dead,
+	///The cells at the other end, as if the row were a ring
+	Wrap,
+	///The cells in the row reflected at its end, so the end cell is its own neighbor
+	Mirror,
+}
+
+impl Boundary {
+	/// Parses a boundary by name.
+	///
+	/// # Arguments
+	/// * 'text' - "dead", "wrap" or "mirror", in any case.
+	///
+	/// # Return
+	/// * Result<Boundary, String> - The boundary, or a description of what is wrong with the name.
+	pub fn parse(text: &str) -> Result<Boundary, String> {
+		match text.trim().to_lowercase().as_str() {
+			"dead" => Ok(Boundary::Dead),
+			"wrap" => Ok(Boundary::Wrap),
+			"mirror" => Ok(Boundary::Mirror),
+			_ => Err("dead, wrap or mirror".to_string()),
+		}
+	}
+}
+
+impl fmt::Display for Boundary {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", match *self {
+			Boundary::Dead => "dead",
+			Boundary::Wrap => "wrap",
+			Boundary::Mirror => "mirror",
+		})
+	}
+}
+
+///How the first row of a diagram is filled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Start {
+	///One living cell in the middle
+	Single,
+	///Each cell alive by chance, from the world's seed
+	Random,
+}
+
+impl Start {
+	/// Parses a first row by name.
+	///
+	/// # Arguments
+	/// * 'text' - "single" or "random", in any case.
+	///
+	/// # Return
+	/// * Result<Start, String> - The first row, or a description of what is wrong with the name.
+	pub fn parse(text: &str) -> Result<Start, String> {
+		match text.trim().to_lowercase().as_str() {
+			"single" => Ok(Start::Single),
+			"random" => Ok(Start::Random),
+			_ => Err("single or random".to_string()),
+		}
+	}
+}
+
+impl fmt::Display for Start {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", match *self {
+			Start::Single => "single",
+			Start::Random => "random",
+		})
+	}
+}
+
+///A one dimensional rule, elementary or totalistic
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Elementary {
+	///Bit n is the next state of a cell whose neighborhood reads n
+	code: u64,
+	///How many cells on each side of a cell are its neighbors. 1 for elementary rules.
+	radius: i32,
+	///True if neighborhoods read as the count of living cells, false if as a binary number
+	totalistic: bool,
+	///What the cells past the ends of a row are taken to be
+	boundary: Boundary,
+}
+
+impl Elementary {
+	/// Parses a rule string such as "W30" for an elementary rule or "T10R2" for a totalistic one.
+	/// Rules start with dead cells past the ends of the row.
+	///
+	/// # Arguments
+	/// * 'text' - The rule string.
+	///
+	/// # Return
+	/// * Result<Elementary, String> - The rule, or a description of what is wrong with the string.
+	pub fn parse(text: &str) -> Result<Elementary, String> {
+		let text = text.trim().to_uppercase();
+		let totalistic = match text.chars().next() {
+			Some('W') => false,
+			Some('T') => true,
+			_ => return Err(format!("expected W0 to W255 or Tn, found '{}'", text)),
+		};
+
+		//Totalistic rules may reach further than the cells on either side
+		let mut parts = text[1..].splitn(2, 'R');
+		let code = parts.next().unwrap_or("");
+		let radius = match parts.next() {
+			Some(_) if !totalistic => return Err("only totalistic rules have a radius, such as T10R2".to_string()),
+			Some(radius) => match radius.parse::<i32>() {
+				Ok(radius) if (1..=MAX_RADIUS).contains(&radius) => radius,
+				_ => return Err(format!("the radius must be from 1 to {}", MAX_RADIUS)),
+			},
+			None => 1,
+		};
+
+		//One bit for each way a neighborhood can read
+		let bits = if totalistic { 2 * radius + 2 } else { 8 };
+		match code.parse::<u64>() {
+			Ok(code) if code < 1 << bits => Ok(Elementary { code, radius, totalistic, boundary: Boundary::Dead }),
+			_ => Err(format!("'{}' is not a rule number from 0 to {}", code, (1u64 << bits) - 1)),
+		}
+	}
+
+	/// Returns what the cells past the ends of a row are taken to be
+	pub fn boundary(&self) -> Boundary {
+		self.boundary
+	}
+
+	/// Generates the same rule with a different boundary.
+	///
+	/// # Arguments
+	/// * 'boundary' - What the cells past the ends of a row are taken to be.
+	pub fn with_boundary(&self, boundary: Boundary) -> Elementary {
+		Elementary { boundary, ..*self }
+	}
+
+	/// Decides the next generation of a row.
+	///
+	/// # Arguments
+	/// * 'row' - The states of the row this generation. Only living cells count.
+	///
+	/// # Return
+	/// * Vec<State> - The states of the row next generation.
+	pub fn next(&self, row: &[State]) -> Vec<State> {
+		let width = row.len() as i32;
+		let alive = |x: i32| {
+			let x = match self.boundary {
+				Boundary::Dead => x,
+				Boundary::Wrap => ((x % width) + width) % width,
+				Boundary::Mirror if x < 0 => -x - 1,
+				Boundary::Mirror if x >= width => 2 * width - x - 1,
+				Boundary::Mirror => x,
+			};
+			//Neighborhoods wider than the row may still reach past the reflection
+			if x >= 0 && x < width && row[x as usize] == ALIVE { 1 } else { 0 }
+		};
+		(0..width).map(|x| {
+			let index = (x - self.radius..x + self.radius + 1).fold(0, |index, x| {
+				if self.totalistic { index + alive(x) } else { index << 1 | alive(x) }
+			});
+			if self.code >> index & 1 == 1 { ALIVE } else { DEAD }
+		}).collect()
+	}
+}
+
+impl fmt::Display for Elementary {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match (self.totalistic, self.radius) {
+			(false, _) => write!(f, "W{}", self.code),
+			(true, 1) => write!(f, "T{}", self.code),
+			(true, radius) => write!(f, "T{}R{}", self.code, radius),
+		}
+	}
+}
+
+/// Used only in testing.
+/// Reads a row written with 'o' for living cells and '.' for dead ones.
+#[allow(dead_code)]
+fn row(text: &str) -> Vec<State> {
+	text.chars().map(|c| if c == 'o' { ALIVE } else { DEAD }).collect()
+}
+
+#[test]
+fn test_elementary_parse() {
+	assert_eq!("W30", Elementary::parse("w30").unwrap().to_string());
+	assert_eq!("T10R2", Elementary::parse(" t10r2 ").unwrap().to_string());
+	assert_eq!("T6", Elementary::parse("T6R1").unwrap().to_string());
+	assert_eq!(Boundary::Dead, Elementary::parse("W110").unwrap().boundary());
+	assert_eq!(Boundary::Wrap, Elementary::parse("W110").unwrap().with_boundary(Boundary::Wrap).boundary());
+
+	assert_eq!(Err("'256' is not a rule number from 0 to 255".to_string()), Elementary::parse("W256"));
+	assert_eq!(Err("'16' is not a rule number from 0 to 15".to_string()), Elementary::parse("T16"));
+	assert!(Elementary::parse("T63R2").is_ok());
+	assert!(Elementary::parse("T64R2").is_err());
+	assert!(Elementary::parse("W30R2").is_err());
+	assert!(Elementary::parse("T1R0").is_err());
+	assert!(Elementary::parse("X30").is_err());
+	assert!(Elementary::parse("W").is_err());
+
+	assert_eq!((Ok(Boundary::Mirror), Ok(Start::Random)), (Boundary::parse("Mirror"), Start::parse("RANDOM")));
+	assert!(Boundary::parse("ring").is_err());
+	assert_eq!(("wrap", "single"), (Boundary::Wrap.to_string().as_str(), Start::Single.to_string().as_str()));
+}
+
+#[test]
+fn test_elementary_next() {
+	let rows = |rule: &Elementary, first: &str, count: usize| {
+		let mut rows = vec![row(first)];
+		for _ in 0..count {
+			let next = rule.next(rows.last().expect("Error reading row"));
+			rows.push(next);
+		}
+		rows
+	};
+
+	//Rule 30 grows chaotically from one cell, and rule 90 draws Sierpinski's triangle
+	let w30 = Elementary::parse("W30").unwrap();
+	assert_eq!(vec![row("...o..."), row("..ooo.."), row(".oo..o."), row("oo.oooo")], rows(&w30, "...o...", 3));
+	let w90 = Elementary::parse("W90").unwrap();
+	assert_eq!(vec![row("...o..."), row("..o.o.."), row(".o...o."), row("o.o.o.o")], rows(&w90, "...o...", 3));
+
+	//Each boundary sees something different past the ends
+	let next = |boundary: Boundary, first: &str| w30.with_boundary(boundary).next(&row(first));
+	assert_eq!((row("oo..."), row("oo..o"), row(".o...")), (next(Boundary::Dead, "o...."), next(Boundary::Wrap, "o...."), next(Boundary::Mirror, "o....")));
+	assert_eq!((row("...oo"), row("o..oo"), row("...oo")), (next(Boundary::Dead, "....o"), next(Boundary::Wrap, "....o"), next(Boundary::Mirror, "....o")));
+
+	//Rule 184 moves cars right in traffic, keeping every car on a ring road
+	let w184 = Elementary::parse("W184").unwrap().with_boundary(Boundary::Wrap);
+	assert_eq!(vec![row("oo.o..o."), row("o.o.o..o"), row(".o.o.o.o")], rows(&w184, "oo.o..o.", 2));
+
+	//Totalistic rules count the living cells around, the cell itself included
+	let t6 = Elementary::parse("T6").unwrap();
+	assert_eq!(vec![row("...o..."), row("..ooo.."), row(".oo.oo."), row("ooooooo")], rows(&t6, "...o...", 3));
+	let t10 = Elementary::parse("T10R2").unwrap();
+	assert_eq!(vec![row("....o...."), row("..ooooo.."), row("o.o...o.o"), row("...o.o...")], rows(&t10, "....o....", 3));
+}
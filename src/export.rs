@@ -0,0 +1,266 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Image export. Pictures of the world are written as PNG files, with the pixel data
+// compressed by a small deflate encoder. It only looks for repeats of the pixel before,
+// which is most of a picture of cells on a plain background.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+///Starts every PNG file
+const PNG_SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+///Bytes in a pixel, red, green, blue and alpha
+const BYTES_PER_PIXEL: usize = 4;
+
+///Shortest and longest repeat deflate can write
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+///Shortest length written by each deflate length code from 257 on, and the extra bits after it
+const LENGTH_BASES: [(usize, u32); 29] = [
+	(3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0),
+	(11, 1), (13, 1), (15, 1), (17, 1), (19, 2), (23, 2), (27, 2), (31, 2),
+	(35, 3), (43, 3), (51, 3), (59, 3), (67, 4), (83, 4), (99, 4), (115, 4),
+	(131, 5), (163, 5), (195, 5), (227, 5), (258, 0),
+];
+
+///Deflate distance code for a repeat of the pixel before
+const DISTANCE_PIXEL: u32 = 3;
+
+///Collects bits least significant first, as deflate packs them
+struct Bits {
+	bytes: Vec<u8>,
+	///Bits waiting to fill a byte, and how many there are
+	pending: (u32, u32),
+}
+
+impl Bits {
+	/// Generates an empty bit stream
+	fn new() -> Self {
+		Bits { bytes: Vec::new(), pending: (0, 0) }
+	}
+
+	/// Adds a value, lowest bit first.
+	///
+	/// # Arguments
+	/// * 'value' - The bits.
+	/// * 'count' - How many of the bits to add.
+	fn push(&mut self, value: u32, count: u32) {
+		self.pending.0 |= value << self.pending.1;
+		self.pending.1 += count;
+		while self.pending.1 >= 8 {
+			self.bytes.push(self.pending.0 as u8);
+			self.pending = (self.pending.0 >> 8, self.pending.1 - 8);
+		}
+	}
+
+	/// Adds a Huffman code, which deflate packs highest bit first.
+	///
+	/// # Arguments
+	/// * 'code'  - The code.
+	/// * 'count' - The length of the code in bits.
+	fn push_code(&mut self, code: u32, count: u32) {
+		self.push(code.reverse_bits() >> (32 - count), count);
+	}
+
+	/// Pads the last byte with zeros and returns the bytes
+	fn finish(mut self) -> Vec<u8> {
+		if self.pending.1 > 0 {
+			self.bytes.push(self.pending.0 as u8);
+		}
+		self.bytes
+	}
+}
+
+/// Writes a literal byte or a length code with deflate's fixed Huffman codes.
+///
+/// # Arguments
+/// * 'bits'   - The stream to write to.
+/// * 'symbol' - 0 to 255 for a literal byte, 256 to end the block, 257 on for a length.
+fn push_symbol(bits: &mut Bits, symbol: u32) {
+	match symbol {
+		0..=143 => bits.push_code(0x30 + symbol, 8),
+		144..=255 => bits.push_code(0x190 + symbol - 144, 9),
+		256..=279 => bits.push_code(symbol - 256, 7),
+		_ => bits.push_code(0xc0 + symbol - 280, 8),
+	}
+}
+
+/// Compresses bytes into a zlib stream of one fixed Huffman deflate block.
+///
+/// # Arguments
+/// * 'data' - The bytes to compress.
+///
+/// # Return
+/// * Vec<u8> - The zlib stream.
+fn deflate(data: &[u8]) -> Vec<u8> {
+	let mut bits = Bits::new();
+	//The only block, with fixed Huffman codes
+	bits.push(1, 1);
+	bits.push(1, 2);
+
+	let mut i = 0;
+	while i < data.len() {
+		//Repeats of the pixel before are written as a length and a distance
+		let run = if i >= BYTES_PER_PIXEL {
+			data[i..].iter().zip(data[i - BYTES_PER_PIXEL..].iter()).take(MAX_MATCH).take_while(|&(a, b)| a == b).count()
+		}
+		else {
+			0
+		};
+		if run < MIN_MATCH {
+			push_symbol(&mut bits, data[i] as u32);
+			i += 1;
+			continue;
+		}
+		let code = LENGTH_BASES.iter().rposition(|&(base, _)| base <= run).expect("Error finding length code");
+		let (base, extra) = LENGTH_BASES[code];
+		push_symbol(&mut bits, 257 + code as u32);
+		bits.push((run - base) as u32, extra);
+		bits.push_code(DISTANCE_PIXEL, 5);
+		i += run;
+	}
+	push_symbol(&mut bits, 256);
+
+	let mut stream = vec![0x78, 0x01];
+	stream.extend(bits.finish());
+	stream.extend_from_slice(&adler32(data).to_be_bytes());
+	stream
+}
+
+/// Finds the Adler-32 checksum zlib ends its streams with.
+///
+/// # Arguments
+/// * 'data' - The bytes to check.
+fn adler32(data: &[u8]) -> u32 {
+	let (mut a, mut b) = (1u32, 0u32);
+	for byte in data {
+		a = (a + *byte as u32) % 65521;
+		b = (b + a) % 65521;
+	}
+	(b << 16) | a
+}
+
+/// Finds the CRC-32 checksum PNG ends its chunks with.
+///
+/// # Arguments
+/// * 'data' - The bytes to check.
+fn crc32(data: &[u8]) -> u32 {
+	let mut crc = 0xffff_ffffu32;
+	for byte in data {
+		crc ^= *byte as u32;
+		for _ in 0..8 {
+			crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+		}
+	}
+	!crc
+}
+
+/// Adds a PNG chunk: its length, type, data and checksum.
+///
+/// # Arguments
+/// * 'png'  - The file being built.
+/// * 'kind' - The four letter type of the chunk.
+/// * 'data' - The contents of the chunk.
+fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+	png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+	let start = png.len();
+	png.extend_from_slice(kind);
+	png.extend_from_slice(data);
+	let crc = crc32(&png[start..]);
+	png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Encodes a picture as a PNG file.
+///
+/// # Arguments
+/// * 'width & height' - The size of the picture in pixels.
+/// * 'rgba'           - Four bytes of color per pixel, row by row.
+///
+/// # Return
+/// * Vec<u8> - The contents of the file.
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+	let mut header = Vec::new();
+	header.extend_from_slice(&width.to_be_bytes());
+	header.extend_from_slice(&height.to_be_bytes());
+	//8 bits a channel, RGBA, then the standard compression, filtering and no interlacing
+	header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+	//Each row starts with its filter, none
+	let mut rows = Vec::with_capacity(rgba.len() + height as usize);
+	for row in rgba.chunks(width as usize * BYTES_PER_PIXEL) {
+		rows.push(0);
+		rows.extend_from_slice(row);
+	}
+
+	let mut png = PNG_SIGNATURE.to_vec();
+	push_chunk(&mut png, b"IHDR", &header);
+	push_chunk(&mut png, b"IDAT", &deflate(&rows));
+	push_chunk(&mut png, b"IEND", &[]);
+	png
+}
+
+/// Saves a picture as a PNG file.
+///
+/// # Arguments
+/// * 'path'           - The file to create or replace.
+/// * 'width & height' - The size of the picture in pixels.
+/// * 'rgba'           - Four bytes of color per pixel, row by row.
+///
+/// # Return
+/// * Result<(), String> - A description of what went wrong, if anything.
+pub fn save_png(path: &Path, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+	File::create(path)
+		.and_then(|mut file| file.write_all(&encode_png(width, height, rgba)))
+		.map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+#[test]
+fn test_export_checksums() {
+	assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+	assert_eq!(0x11e6_0398, adler32(b"Wikipedia"));
+	//Every PNG file ends the same way
+	let mut end = Vec::new();
+	push_chunk(&mut end, b"IEND", &[]);
+	assert_eq!(vec![0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82], end);
+}
+
+#[test]
+fn test_export_deflate() {
+	//Bytes that don't repeat are written as they are: 8 bit codes for bytes up to 143
+	assert_eq!(vec![0x78, 0x01, 0x73, 0x04, 0x00, 0x00, 0x42, 0x00, 0x42], deflate(b"A"));
+
+	//A run of one pixel is its first copy, then a length and distance, much smaller than the run
+	let run: Vec<u8> = (0..1000).flat_map(|_| vec![10, 20, 30, 255]).collect();
+	let stream = deflate(&run);
+	assert!(stream.len() < 40);
+	assert_eq!(&adler32(&run).to_be_bytes(), &stream[stream.len() - 4..]);
+}
+
+#[test]
+fn test_export_png() {
+	let rgba = vec![255, 0, 0, 255, 0, 0, 255, 255, 0, 0, 0, 0, 255, 255, 255, 255];
+	let png = encode_png(2, 2, &rgba);
+	assert_eq!(&PNG_SIGNATURE, &png[..8]);
+	//The header chunk gives the size, 8 bit RGBA
+	assert_eq!(&[0, 0, 0, 13], &png[8..12]);
+	assert_eq!(b"IHDR", &png[12..16]);
+	assert_eq!(&[0, 0, 0, 2, 0, 0, 0, 2, 8, 6, 0, 0, 0], &png[16..29]);
+	assert_eq!(&crc32(&png[12..29]).to_be_bytes(), &png[29..33]);
+	assert_eq!(b"IDAT", &png[37..41]);
+	assert!(png.ends_with(&[b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+}
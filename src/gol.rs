@@ -14,29 +14,168 @@
 
 // A basic Game of Life implementation for the ggez 2d gaming environment. 
 
-extern crate rand;
-use self::rand::Rng;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::mem;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
-extern crate ggez;
-use ggez::{Context, graphics};
+extern crate rand;
+use self::rand::{Rng, SeedableRng};
+use self::rand::rngs::StdRng;
 
 use params::{
-	SIZE_GRID_PIXELS,
 	WIDTH_GAME_GRIDS, HEIGHT_GAME_GRIDS
 };
 
+use camera::{Camera};
+use elementary::{Elementary, Start};
+use pattern::{Pattern};
+use recorder::{Recorder};
+use render::{Color, Gradient, Renderer};
+use neighborhood::{Neighborhood};
+use rule::{Rule, Range, State, DEAD, ALIVE, HEAD, TAIL, CONDUCTOR, MAX_COLORS};
+
+///Color of living cells
+const COLOR_CELL: Color = [0.5, 0.5, 0.5, 0.9];
+
+///Colors of living cells by age while ages are tracked, from newborn orange to old dark blue
+const AGE_COLORS: [(u32, Color); 4] = [
+	(1, [0.95, 0.6, 0.1, 0.9]),
+	(10, [0.8, 0.2, 0.3, 0.9]),
+	(100, [0.4, 0.2, 0.6, 0.9]),
+	(1000, [0.1, 0.15, 0.4, 0.9]),
+];
+
+///Colors of dying cells under Generations rules, from just dying to nearly dead
+const COLOR_DYING: Color = [0.3, 0.45, 0.9, 0.8];
+const COLOR_NEARLY_DEAD: Color = [0.3, 0.45, 0.9, 0.15];
+
+///Colors of living cells under Immigration and QuadLife, one for each player
+const PLAYER_COLORS: [Color; MAX_COLORS] = [
+	[0.85, 0.2, 0.2, 0.9],
+	[0.2, 0.4, 0.9, 0.9],
+	[0.2, 0.7, 0.3, 0.9],
+	[0.9, 0.7, 0.1, 0.9],
+];
+
+///Colors of WireWorld cells: electron heads, electron tails and conductors
+const COLOR_HEAD: Color = [0.2, 0.45, 1.0, 0.95];
+const COLOR_TAIL: Color = [0.95, 0.3, 0.2, 0.9];
+const COLOR_CONDUCTOR: Color = [0.95, 0.7, 0.1, 0.8];
+
+///Colors of cells born in the last generation and cells that just died, while changes are shown
+const COLOR_BORN: Color = [0.2, 0.75, 0.3, 0.9];
+const COLOR_DIED: Color = [0.9, 0.3, 0.2, 0.6];
+
+///Generations the mark left where a cell died takes to fade, while trails are shown
+const TRAIL_LENGTH: u8 = 20;
+
+///Colors of trails by generations since the cell died, fading out
+const TRAIL_COLORS: [(u32, Color); 2] = [
+	(1, [0.4, 0.5, 0.9, 0.6]),
+	(TRAIL_LENGTH as u32, [0.4, 0.5, 0.9, 0.05]),
+];
+
+///Colors of the heatmap by how often a cell has been alive, in thousandths of the most any cell has
+const HEAT_COLORS: [(u32, Color); 3] = [
+	(0, [1.0, 0.95, 0.5, 0.4]),
+	(300, [1.0, 0.6, 0.2, 0.6]),
+	(1000, [0.85, 0.1, 0.1, 0.8]),
+];
+
+///Color of the world behind its cells, so the edges of the map show
+const COLOR_WORLD: Color = [0.96, 0.96, 0.96, 1.0];
+
+///Corners of a hex cell around its middle, in cells. Each edge sits halfway to a neighbor's middle.
+const HEX_CORNERS: [(f32, f32); 6] = [(0.0, -0.625), (0.5, -0.375), (0.5, 0.375), (0.0, 0.625), (-0.5, 0.375), (-0.5, -0.375)];
+
+///Meta data about a world, as of its last generation or edit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+	///Generations run
+	pub generation: u64,
+	///Cells alive now
+	pub living: u64,
+	///Cells born in the last generation
+	pub births: u64,
+	///Cells that died in the last generation
+	pub deaths: u64,
+	///Cells the last generation changed, born or died
+	pub changed: u64,
+	///Cells born in every generation so far
+	pub total_births: u64,
+	///Cells that died in every generation so far
+	pub total_deaths: u64,
+	///Most cells alive at once
+	pub peak: u64,
+	///Generation the peak was first reached
+	pub peak_generation: u64,
+	///Smallest block holding every living cell as (x, y, width, height), None if nothing is alive
+	pub bounds: Option<(i32, i32, i32, i32)>,
+	///Fraction of the map alive, from 0 to 1
+	pub density: f64,
+	///Cells alive now of each color, all 0 under rules without colors
+	pub colors: [u64; MAX_COLORS],
+}
+
+///Extra layers drawn with the living cells. What each needs is only tracked while it is shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Overlay {
+	///Cells born in the last generation in green, and cells that just died in red
+	Changes,
+	///Fading marks where cells recently died
+	Trails,
+	///How often each cell has ever been alive, from yellow to red, under the living cells
+	Heatmap,
+}
+
+///Every overlay, in the order they are listed
+pub const OVERLAYS: [Overlay; 3] = [Overlay::Changes, Overlay::Trails, Overlay::Heatmap];
+
 /// Defines the map and meta data
 pub struct World {
-	/// A 2D vector of options representing cells on the map:
-	/// * A cell is alive if it is true
-	/// * A cell is dead if it is false
-	map: Vec<Vec<bool>>,
+	/// A 2D vector of the state of each cell on the map:
+	/// * A cell is alive if it is ALIVE
+	/// * A cell is dead if it is DEAD
+	/// * Any other state is a dying cell, under rules with more than two states
+	map: Vec<Vec<State>>,
 	///Tracks the number of times the world updates 
-	generation:i32,
+	generation: u64,
 	///Tracks living cells
-	living: i32, 
+	living: u64,
 	///Tracks total of living cells that died
-	dead: i32,
+	dead: u64,
+	///Tracks total of cells born
+	born: u64,
+	///Cells born and cells that died in the last generation
+	changes: (u64, u64),
+	///Most cells alive at once, and the generation it was first reached
+	peak: (u64, u64),
+	///Bounds of the living cells as found by the last generation, None once an edit may have moved them
+	bounds: Option<Option<(i32, i32, i32, i32)>>,
+	///Decides which cells live and die each generation
+	rule: Rule,
+	///Cells edited while recording, with their old and new states
+	journal: Option<Vec<(i32, i32, State, State)>>,
+	///Writes the stats of every generation, None when not recording
+	recorder: Option<Recorder>,
+	///Generations each cell has been alive, 0 for dead cells. None when ages aren't tracked.
+	ages: Option<Vec<Vec<u32>>>,
+	///Colors living cells by age while ages are tracked
+	gradient: Gradient,
+	///The map before the last generation, while changes are shown
+	previous: Option<Vec<Vec<State>>>,
+	///Generations left before the mark where each cell died fades, while trails are shown
+	trails: Option<Vec<Vec<u8>>>,
+	///Generations each cell has been alive and the most of any cell, while the heatmap is shown
+	heat: Option<(Vec<Vec<u32>>, u32)>,
+	///Color of each cell while the rule has colors. Only the colors of living cells mean anything.
+	colors: Option<Vec<Vec<u8>>>,
+	///Living cells of each color
+	color_counts: [u64; MAX_COLORS],
+	///Color that edits bring cells to life in
+	paint: u8,
 }
 
 impl World {
@@ -46,16 +185,54 @@ impl World {
 	///
 	/// * 'num_pop' - The initial population. Each population is located randomly.
 	pub fn new (num_pop: i32) -> Self {
-		let mut range = rand::thread_rng();
-		let mut locals: Vec<Vec<bool>> = vec![vec![false; WIDTH_GAME_GRIDS as usize]; HEIGHT_GAME_GRIDS as usize];  
+		World::generate(num_pop, (WIDTH_GAME_GRIDS, HEIGHT_GAME_GRIDS), None)
+	}
 
-		for _i in 0..num_pop {
-			let x = range.gen_range::<i32>(0, WIDTH_GAME_GRIDS as i32);
-			let y = range.gen_range::<i32>(0, HEIGHT_GAME_GRIDS as i32);
-			locals[y as usize][x as usize] = true;
+	/// Generates a new world given its population, size, and random seed
+	///
+	/// # Arguments
+	///
+	/// * 'num_pop' - The initial population. Each population is located randomly.
+	/// * 'size' - The (width, height) of the map.
+	/// * 'seed' - Seed for placing the population. The same seed always places it the same way. None picks a random seed.
+	pub fn generate (num_pop: i32, size: (i32, i32), seed: Option<u64>) -> Self {
+		let mut locals: Vec<Vec<State>> = vec![vec![DEAD; size.0 as usize]; size.1 as usize];  
+		let num_pop = num_pop.max(0).min(size.0 * size.1);
+
+		let mut range = seeded(seed);
+
+		//Keep placing life until the population is reached, cells can only be populated once
+		let mut living: u64 = 0;
+		while living < num_pop as u64 {
+			let x = range.gen_range::<i32>(0, size.0);
+			let y = range.gen_range::<i32>(0, size.1);
+			if locals[y as usize][x as usize] == DEAD {
+				locals[y as usize][x as usize] = ALIVE;
+				living += 1;
+			}
 		}
 
-		World { map: locals, generation:0, living: num_pop, dead: 0,}
+		World {
+			map: locals,
+			generation: 0,
+			living,
+			dead: 0,
+			born: 0,
+			changes: (0, 0),
+			peak: (living, 0),
+			bounds: None,
+			rule: Rule::classic(),
+			journal: None,
+			recorder: None,
+			ages: None,
+			gradient: Gradient::new(AGE_COLORS.to_vec()),
+			previous: None,
+			trails: None,
+			heat: None,
+			colors: None,
+			color_counts: [0; MAX_COLORS],
+			paint: 0,
+		}
 	}
 
 	/// Used only in testing. 
@@ -68,45 +245,224 @@ impl World {
 	/// * 'living' - The desired count of living cells
 	/// * 'dead' - The desired count of dead cells
 	#[allow(dead_code)]
-	pub fn set_all (map: Vec<Vec<bool>>, generation:i32, living: i32, dead: i32) -> Self {
+	pub fn set_all (map: Vec<Vec<bool>>, generation: u64, living: u64, dead: u64) -> Self {
 		World { 
-			map: map, 
+			map: map.into_iter().map(|row| row.into_iter().map(|life| if life { ALIVE } else { DEAD }).collect()).collect(), 
 			generation: generation, 
 			living: living, 
 			dead: dead,
+			born: 0,
+			changes: (0, 0),
+			peak: (living, generation),
+			bounds: None,
+			rule: Rule::classic(),
+			journal: None,
+			recorder: None,
+			ages: None,
+			gradient: Gradient::new(AGE_COLORS.to_vec()),
+			previous: None,
+			trails: None,
+			heat: None,
+			colors: None,
+			color_counts: [0; MAX_COLORS],
+			paint: 0,
+		}
+	}
+
+	/// Returns the rule the world follows
+	pub fn rule(&self) -> &Rule {
+		&self.rule
+	}
+
+	/// Changes the rule the world follows from the next generation on. A rule bringing
+	/// colors deals them out to the cells at random, the same way for the same map.
+	///
+	/// # Arguments
+	/// * 'rule' - The new rule.
+	pub fn set_rule(&mut self, rule: Rule) {
+		if rule.colors() == 0 {
+			self.colors = None;
+		}
+		else if self.colors.is_none() || rule.colors() != self.rule.colors() {
+			let mut range = seeded(Some(self.fingerprint()));
+			self.colors = Some(self.map.iter().map(|row| {
+				row.iter().map(|_| range.gen_range::<u8>(0, rule.colors())).collect()
+			}).collect());
+			self.paint = 0;
+		}
+		self.rule = rule;
+
+		let mut counts = [0; MAX_COLORS];
+		if let Some(ref colors) = self.colors {
+			for (row, colors) in self.map.iter().zip(colors.iter()) {
+				for (state, color) in row.iter().zip(colors.iter()) {
+					if *state == ALIVE {
+						counts[*color as usize] += 1;
+					}
+				}
+			}
 		}
+		self.color_counts = counts;
 	}
 
-	/// The classic rules of a Game of Life generation
+	/// The rules of a Game of Life generation. Classic rules unless the world was given another.
 	/// * Make a static copy of the map to evaluate while updating the working map
-	/// * Gather the number of neighbors a cell has
+	/// * Gather the number of neighbors a cell has, further away under Larger than Life rules
 	/// * Kill a living cell if there are too many/few neighbors
-	/// * Spawn life in a dead cell if it has exactly 3 living neighbors
+	/// * Spawn life in a dead cell whose neighbor count the rule's birth set contains
+	/// * Move dying cells on to their next state, under rules with more than two states
 	pub fn clasic_generation(&mut self) {
+		if let Some(line) = self.rule.line() {
+			self.line_generation(line);
+			return;
+		}
+
 		//Capture the state of this generation's map
-		let generation: Vec<Vec<bool>> = self.map.to_vec();
+		let generation: Vec<Vec<State>> = self.map.to_vec();
 		let mut born = 0;
 		let mut died = 0;
+		let mut color_counts = [0; MAX_COLORS];
+		//Corners of the living cells as (x0, y0, x1, y1), found on the way past
+		let mut corners: Option<(usize, usize, usize, usize)> = None;
+		//Larger than Life rules reaching past the cells around count every cell's neighbors up front
+		let counts = match (self.rule.radius(), self.rule.middle()) {
+			(1, false) => None,
+			(radius, middle) => Some(range_counts(&generation, radius, self.rule.range(), middle)),
+		};
 
 		//Evaluate each cell for living neighbors 
 		for (y, row) in generation.iter().enumerate() {
 			for (x, cell) in row.iter().enumerate() {
-				let live_neighbors = World::num_neighbors(&generation, x as i32, y as i32);
-				//Some = populated, check for population collaps
-				//None = unpopulated, check for population growth
-				match cell {
-					true => { if live_neighbors < 2 || live_neighbors > 3 { self.map[y][x] = false; died +=1} },
-					false => { if live_neighbors == 3 { self.map[y][x] = true; born += 1;} },
+				let live_neighbors = match counts {
+					Some(ref counts) => counts[y][x],
+					None => self.rule.neighbors(&generation, x as i32, y as i32),
+				};
+				//Living cells that leave ALIVE died, whether they are dead or dying now
+				let next = self.rule.next(*cell, live_neighbors);
+				let (was_alive, alive) = (*cell == ALIVE, next == ALIVE);
+				self.map[y][x] = next;
+				match (was_alive, alive) {
+					(true, false) => died += 1,
+					(false, true) => born += 1,
+					_ => (),
+				}
+				if let Some(ref mut ages) = self.ages {
+					ages[y][x] = if alive { ages[y][x].saturating_add(1) } else { 0 };
+				}
+				if let Some(ref mut trails) = self.trails {
+					trails[y][x] = match (was_alive, alive) {
+						(true, false) => TRAIL_LENGTH,
+						(_, true) => 0,
+						(false, false) => trails[y][x].saturating_sub(1),
+					};
+				}
+				//Parents stay alive while their newborns are colored, so their colors are this generation's
+				if let Some(ref mut colors) = self.colors {
+					if alive && !was_alive {
+						let parents = self.rule.neighborhood().count_colors(&generation, colors, x as i32, y as i32);
+						colors[y][x] = self.rule.newborn_color(&parents[..self.rule.colors() as usize]);
+					}
+					if alive {
+						color_counts[colors[y][x] as usize] += 1;
+					}
+				}
+				if let Some((ref mut heat, ref mut hottest)) = self.heat {
+					if alive {
+						heat[y][x] = heat[y][x].saturating_add(1);
+						*hottest = (*hottest).max(heat[y][x]);
+					}
+				}
+				if alive {
+					corners = Some(match corners {
+						Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+						None => (x, y, x, y),
+					});
 				}
 			}
 		}
 
 		self.generation += 1;
-		self.living += born - died;
+		self.living = self.living + born - died;
 		self.dead += died;
+		self.born += born;
+		self.changes = (born, died);
+		self.color_counts = color_counts;
+		self.bounds = Some(corners.map(|(x0, y0, x1, y1)| {
+			(x0 as i32, y0 as i32, (x1 - x0 + 1) as i32, (y1 - y0 + 1) as i32)
+		}));
+		self.note_peak();
+		if self.previous.is_some() {
+			self.previous = Some(generation);
+		}
 	}
 
-	/// Determines the number of living neighbors around a target in a map of coordinates
+	/// A generation of a one dimensional rule, growing the space-time diagram by a row.
+	/// * The row of the latest generation is the one the generation count reaches, or the bottom row past it
+	/// * The next row goes below it, or once the rows reach the bottom, the map scrolls up a row to make room
+	/// * Cells on the row written over or scrolled off the top died, and living cells on the new row were born
+	/// * Ages and overlays move with their rows
+	///
+	/// # Arguments
+	/// * 'line' - The one dimensional rule.
+	fn line_generation(&mut self, line: Elementary) {
+		let latest = (self.generation as usize).min(self.map.len() - 1);
+		let next = line.next(&self.map[latest]);
+		let width = next.len();
+		let living = |row: &Vec<State>| row.iter().filter(|state| **state == ALIVE).count() as u64;
+
+		let born = living(&next);
+		if let Some(ref mut ages) = self.ages {
+			put_row(ages, latest, next.iter().map(|state| if *state == ALIVE { 1 } else { 0 }).collect());
+		}
+		if let Some(ref mut trails) = self.trails {
+			put_row(trails, latest, vec![0; width]);
+		}
+		if let Some((ref mut heat, ref mut hottest)) = self.heat {
+			put_row(heat, latest, next.iter().map(|state| if *state == ALIVE { 1 } else { 0 }).collect());
+			*hottest = (*hottest).max(if born > 0 { 1 } else { 0 });
+		}
+		//The changes overlay shows the whole new row as born
+		if self.previous.is_some() {
+			let mut previous = self.map.to_vec();
+			put_row(&mut previous, latest, vec![DEAD; width]);
+			self.previous = Some(previous);
+		}
+		let died = living(&put_row(&mut self.map, latest, next));
+
+		self.generation += 1;
+		self.living = self.living + born - died;
+		self.dead += died;
+		self.born += born;
+		self.changes = (born, died);
+		self.bounds = None;
+		self.note_peak();
+	}
+
+	/// Fills the first row of a one dimensional rule's space-time diagram, leaving the rest of the map dead.
+	///
+	/// # Arguments
+	/// * 'start' - How to fill the row.
+	/// * 'seed'  - Seed for a random row. None picks a random seed.
+	pub fn start_line(&mut self, start: Start, seed: Option<u64>) {
+		let (width, height) = self.size();
+		self.fill((0, 0, width, height), false);
+		match start {
+			Start::Single => {
+				self.set_cell(width / 2, 0, true);
+			},
+			Start::Random => self.scatter((0, 0, width, 1), 0.5, seed),
+		}
+	}
+
+	/// Remembers the population if it is the largest so far
+	fn note_peak(&mut self) {
+		if self.living > self.peak.0 {
+			self.peak = (self.living, self.generation);
+		}
+	}
+
+	/// Used only in testing.
+	/// Determines the number of living neighbors among the eight cells around a target in a map of coordinates
 	///
 	/// # Arguments
 	/// * 'map' - The map of coordinates.
@@ -114,118 +470,947 @@ impl World {
 	///
 	/// # Return
 	/// * i32 - The number of living neighbors around the target.
-	pub fn num_neighbors(map:&Vec<Vec<bool>>, x:i32, y:i32) -> i32 {
-		let neighbor = |x:i32, y:i32| {
-			match map[y as usize][x as usize] {
-				true => 1, //Living neighbor
-				false => 0, //Dead neighbor
+	#[allow(dead_code)]
+	pub fn num_neighbors(map:&[Vec<State>], x:i32, y:i32) -> i32 {
+		Neighborhood::moore().count(map, x, y)
+	}
+
+	/// Maps a pixel in the window to the cell drawn there, which may be off the map.
+	/// Hex cells are found by the nearest middle, so clicks land on the hexagon drawn.
+	///
+	/// # Arguments
+	/// * 'camera' - Decides which cells are drawn where.
+	/// * 'x & y'  - The target pixel.
+	///
+	/// # Return
+	/// * Option<(i32, i32)> - The (x, y) coordinate of the cell, None if the pixel is outside of the viewport.
+	pub fn cell_at(&self, camera: &Camera, x: i32, y: i32) -> Option<(i32, i32)> {
+		if !self.rule.neighborhood().is_hex() {
+			return camera.cell_at(x, y);
+		}
+		if !camera.contains(x as f32, y as f32) {
+			return None;
+		}
+		let (x, y) = camera.to_world(x as f32 + 0.5, y as f32 + 0.5);
+		//The nearest middle is in the row under the pixel or a row next to it
+		let row = y.floor() as i32;
+		let mut nearest: Option<(f32, (i32, i32))> = None;
+		for row in row - 1..row + 2 {
+			let shift = if row & 1 == 1 { 0.5 } else { 0.0 };
+			let cell = ((x - shift).floor() as i32, row);
+			let (cx, cy) = hex_middle(cell.0, cell.1);
+			let distance = (cx - x) * (cx - x) + (cy - y) * (cy - y);
+			match nearest {
+				Some((closest, _)) if closest <= distance => (),
+				_ => nearest = Some((distance, cell)),
 			}
+		}
+		nearest.map(|(_, cell)| cell)
+	}
+
+	/// Maps a pixel in the window to the cell drawn there.
+	///
+	/// # Arguments
+	/// * 'camera' - Decides which cells are drawn where.
+	/// * 'x & y'  - The target pixel.
+	///
+	/// # Return
+	/// * Option<(i32, i32)> - The (x, y) coordinate of the cell, None if the pixel is outside of the map.
+	pub fn grid_at(&self, camera: &Camera, x: i32, y: i32) -> Option<(i32, i32)> {
+		match self.cell_at(camera, x, y) {
+			Some(cell) if self.get_cell(cell.0, cell.1).is_some() => Some(cell),
+			_ => None,
+		}
+	}
+
+	/// Returns the (width, height) of the map
+	pub fn size(&self) -> (i32, i32) {
+		(self.map.first().map_or(0, |row| row.len()) as i32, self.map.len() as i32)
+	}
+
+	/// Finds the smallest block of cells holding every living cell.
+	///
+	/// # Return
+	/// * Option<(i32, i32, i32, i32)> - The block as (x, y, width, height), None if nothing is alive.
+	pub fn bounds(&self) -> Option<(i32, i32, i32, i32)> {
+		let mut bounds: Option<(i32, i32, i32, i32)> = None;
+		for (y, row) in self.map.iter().enumerate() {
+			for (x, state) in row.iter().enumerate() {
+				if *state == ALIVE {
+					let (x, y) = (x as i32, y as i32);
+					bounds = Some(match bounds {
+						Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+						None => (x, y, x, y),
+					});
+				}
+			}
+		}
+		bounds.map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+	}
+
+	/// Looks up a cell in the map.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	///
+	/// # Return
+	/// * Option<bool> - True if the cell is alive, None if the coordinate is outside of the map.
+	pub fn get_cell(&self, x: i32, y: i32) -> Option<bool> {
+		self.get_state(x, y).map(|state| state == ALIVE)
+	}
+
+	/// Looks up the state of a cell in the map.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	///
+	/// # Return
+	/// * Option<State> - The state of the cell, None if the coordinate is outside of the map.
+	pub fn get_state(&self, x: i32, y: i32) -> Option<State> {
+		if x < 0 || y < 0 {
+			return None;
+		}
+		match self.map.get(y as usize) {
+			Some(row) => row.get(x as usize).cloned(),
+			None => None,
+		}
+	}
+
+	/// Brings a cell to life or kills it, keeping the count of living cells.
+	/// Coordinates outside of the map are ignored.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	/// * 'alive' - The new state of the cell.
+	///
+	/// # Return
+	/// * bool - True if the cell changed.
+	pub fn set_cell(&mut self, x: i32, y: i32, alive: bool) -> bool {
+		self.set_state(x, y, if alive { ALIVE } else { DEAD })
+	}
+
+	/// Puts a cell in a state, keeping the count of living cells.
+	/// Coordinates outside of the map are ignored.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	/// * 'state' - The new state of the cell.
+	///
+	/// # Return
+	/// * bool - True if the cell changed.
+	pub fn set_state(&mut self, x: i32, y: i32, state: State) -> bool {
+		match self.get_state(x, y) {
+			Some(old) if old != state => {
+				let alive = state == ALIVE;
+				if let Some(ref mut colors) = self.colors {
+					let color = &mut colors[y as usize][x as usize];
+					if old == ALIVE {
+						self.color_counts[*color as usize] -= 1;
+					}
+					if alive {
+						*color = self.paint;
+						self.color_counts[*color as usize] += 1;
+					}
+				}
+				self.map[y as usize][x as usize] = state;
+				if alive {
+					self.living += 1;
+					self.note_peak();
+				}
+				else if old == ALIVE {
+					self.living -= 1;
+				}
+				self.bounds = None;
+				if let Some(ref mut ages) = self.ages {
+					ages[y as usize][x as usize] = if alive { 1 } else { 0 };
+				}
+				//Edits aren't births or deaths, so they leave no changes or trails
+				if let Some(ref mut previous) = self.previous {
+					previous[y as usize][x as usize] = state;
+				}
+				if let Some(ref mut trails) = self.trails {
+					trails[y as usize][x as usize] = 0;
+				}
+				if let Some(ref mut journal) = self.journal {
+					journal.push((x, y, old, state));
+				}
+				true
+			},
+			_ => false,
+		}
+	}
+
+	/// Returns the color of a living cell under a rule with colors. Coordinates
+	/// outside of the map, dead cells and rules without colors give None.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	pub fn color(&self, x: i32, y: i32) -> Option<u8> {
+		match (self.get_state(x, y), self.colors.as_ref()) {
+			(Some(ALIVE), Some(colors)) => Some(colors[y as usize][x as usize]),
+			_ => None,
+		}
+	}
+
+	/// Moves on to the next color for edits to bring cells to life in, under a rule with colors.
+	///
+	/// # Return
+	/// * Option<u8> - The new color, None under rules without colors.
+	pub fn next_paint(&mut self) -> Option<u8> {
+		match self.rule.colors() {
+			0 => None,
+			colors => {
+				self.paint = (self.paint + 1) % colors;
+				Some(self.paint)
+			},
+		}
+	}
+
+	/// Starts or stops tracking how many generations each cell has been alive. While ages are
+	/// tracked, living cells are colored by age. Cells alive when tracking starts count from 1.
+	///
+	/// # Arguments
+	/// * 'track' - True to track ages. Ages already being tracked are kept.
+	pub fn track_ages(&mut self, track: bool) {
+		if !track {
+			self.ages = None;
+		}
+		else if self.ages.is_none() {
+			self.ages = Some(self.map.iter().map(|row| {
+				row.iter().map(|state| if *state == ALIVE { 1 } else { 0 }).collect()
+			}).collect());
+		}
+	}
+
+	/// Looks up how many generations a cell has been alive, counting the generation it was born in.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	///
+	/// # Return
+	/// * Option<u32> - The age of the cell, 0 if it is dead. None if ages aren't tracked or the coordinate is outside of the map.
+	pub fn age(&self, x: i32, y: i32) -> Option<u32> {
+		if x < 0 || y < 0 {
+			return None;
+		}
+		self.ages.as_ref()
+			.and_then(|ages| ages.get(y as usize))
+			.and_then(|row| row.get(x as usize).cloned())
+	}
+
+	/// Returns the colors living cells are given by age
+	pub fn gradient(&self) -> &Gradient {
+		&self.gradient
+	}
+
+	/// Changes the colors living cells are given by age.
+	///
+	/// # Arguments
+	/// * 'gradient' - Picks a color for each age, starting from 1.
+	pub fn set_gradient(&mut self, gradient: Gradient) {
+		self.gradient = gradient;
+	}
+
+	/// Shows or hides an overlay. Overlays only know about generations run while they are shown.
+	///
+	/// # Arguments
+	/// * 'overlay' - The overlay.
+	/// * 'shown'   - True to show it. An overlay already shown is kept as it is.
+	pub fn show_overlay(&mut self, overlay: Overlay, shown: bool) {
+		match overlay {
+			Overlay::Changes if !shown => self.previous = None,
+			Overlay::Changes if self.previous.is_none() => self.previous = Some(self.map.clone()),
+			Overlay::Trails if !shown => self.trails = None,
+			Overlay::Trails if self.trails.is_none() => {
+				self.trails = Some(self.map.iter().map(|row| vec![0; row.len()]).collect());
+			},
+			Overlay::Heatmap if !shown => self.heat = None,
+			Overlay::Heatmap if self.heat.is_none() => {
+				//Cells alive now count as alive once
+				let heat = self.map.iter().map(|row| {
+					row.iter().map(|state| if *state == ALIVE { 1 } else { 0 }).collect()
+				}).collect();
+				self.heat = Some((heat, if self.living > 0 { 1 } else { 0 }));
+			},
+			_ => (),
+		}
+	}
+
+	/// Starts noting every cell that is edited, so the edits can be undone as one.
+	/// Cells changed by generations are not noted.
+	pub fn record(&mut self) {
+		self.journal = Some(Vec::new());
+	}
+
+	/// Stops noting edits.
+	///
+	/// # Return
+	/// * Vec<(i32, i32, State, State)> - Each cell changed since recording started, with its old and new states, in order.
+	pub fn take_changes(&mut self) -> Vec<(i32, i32, State, State)> {
+		self.journal.take().unwrap_or_default()
+	}
+
+	/// Flips a cell between alive and dead. Dying cells are brought back to life.
+	/// Under WireWorld, empty cells become conductors and any other cell is emptied.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	pub fn toggle_cell(&mut self, x: i32, y: i32) {
+		let painted = self.rule.painted();
+		if let Some(state) = self.get_state(x, y) {
+			let next = if state == DEAD || (state != ALIVE && painted == ALIVE) { painted } else { DEAD };
+			self.set_state(x, y, next);
+		}
+	}
+
+	/// Sends an electron along a WireWorld wire, moving a cell from conductor to electron head,
+	/// head to tail, and tail back to conductor. Empty cells and other rules are left alone.
+	///
+	/// # Arguments
+	/// * 'x & y' - The target coordinate.
+	pub fn spark(&mut self, x: i32, y: i32) {
+		if !self.rule.is_wireworld() {
+			return;
+		}
+		let next = match self.get_state(x, y) {
+			Some(CONDUCTOR) => HEAD,
+			Some(HEAD) => TAIL,
+			Some(TAIL) => CONDUCTOR,
+			_ => return,
 		};
+		self.set_state(x, y, next);
+	}
+
+	/// Used only in testing.
+	/// Sets every cell on the line between two coordinates alive or dead.
+	///
+	/// # Arguments
+	/// * 'from & to' - The end points of the line.
+	/// * 'alive'     - The new state of the cells.
+	#[allow(dead_code)]
+	pub fn set_line(&mut self, from: (i32, i32), to: (i32, i32), alive: bool) {
+		self.set_line_state(from, to, if alive { ALIVE } else { DEAD });
+	}
+
+	/// Puts every cell on the line between two coordinates in a state. Used to paint with the mouse,
+	/// which can skip cells when it moves quickly.
+	///
+	/// # Arguments
+	/// * 'from & to' - The end points of the line.
+	/// * 'state'     - The new state of the cells.
+	pub fn set_line_state(&mut self, from: (i32, i32), to: (i32, i32), state: State) {
+		//Bresenham's line algorithm
+		let (mut x, mut y) = from;
+		let dx = (to.0 - x).abs();
+		let dy = -(to.1 - y).abs();
+		let sx = if x < to.0 { 1 } else { -1 };
+		let sy = if y < to.1 { 1 } else { -1 };
+		let mut err = dx + dy;
+		loop {
+			self.set_state(x, y, state);
+			if x == to.0 && y == to.1 {
+				break;
+			}
+			let e2 = 2 * err;
+			if e2 >= dy { err += dy; x += sx; }
+			if e2 <= dx { err += dx; y += sy; }
+		}
+	}
 
-		//Only check indexes that need to be checked
-		let tleft  = |a:i32, b:i32| neighbor(a-1, b-1);
-		let top    = |a:i32, b:i32| neighbor(a  , b-1);
-		let tright = |a:i32, b:i32| neighbor(a+1, b-1);
-		let left   = |a:i32, b:i32| neighbor(a-1, b  );
-		let right  = |a:i32, b:i32| neighbor(a+1, b  );
-		let bleft  = |a:i32, b:i32| neighbor(a-1, b+1);
-		let bottom = |a:i32, b:i32| neighbor(a  , b+1);
-		let bright = |a:i32, b:i32| neighbor(a+1, b+1);   
-
-		// t n
-		// n n
-		if x == 0 && y == 0 {
-			right(x, y) + bottom(x, y) + bright(x, y)
-		}
-		// n t
-		// n n
-		else if x == WIDTH_GAME_GRIDS-1 && y == 0 {
-			left(x, y)+bleft(x, y)+bottom(x, y)
-		}
-		// n n
-		// t n
-		else if x == 0 && y == HEIGHT_GAME_GRIDS-1 {
-			top(x, y)+tright(x, y)+right(x, y)
-		}
-		// n n
-		// n t
-		else if x == WIDTH_GAME_GRIDS-1 && y == HEIGHT_GAME_GRIDS-1 {
-			tleft(x, y)+top(x, y)+left(x, y)
-		}
-		// n n
-		// t n
-		// n n
-		else if x == 0 && y > 0 && y < HEIGHT_GAME_GRIDS-1 {
-			top(x, y)+tright(x, y)+right(x, y)+bottom(x, y)+bright(x, y)
-		}
-		// n t n
-		// n n n
-		else if y == 0 && x > 0 && x < WIDTH_GAME_GRIDS-1 {
-			left(x, y)+right(x, y)+bleft(x, y)+bottom(x, y)+bright(x, y)
-		}
-		// n n
-		// n t
-		// n n
-		else if x == WIDTH_GAME_GRIDS-1 && y > 0 && y < HEIGHT_GAME_GRIDS-1 {
-			tleft(x, y)+top(x, y)+left(x, y)+bleft(x, y)+bottom(x, y)
-		}
-		// n n n
-		// n t n
-		else if y == HEIGHT_GAME_GRIDS-1 && x > 0 && x < WIDTH_GAME_GRIDS-1 {
-			tleft(x, y)+top(x, y)+tright(x, y)+left(x, y)+right(x, y)
-		}
-		// n n n
-		// n t n
-		// n n n
-		else {
-			tleft(x, y)+top(x, y)+tright(x, y)+left(x, y)+right(x, y)+bleft(x, y)+bottom(x, y)+bright(x, y)
-		}
-	}
-
-	/// Updates cells in the map and returns a tuple of world meta data.
+	/// Sets the cells of a pattern to their states. Cells that land outside of the map are dropped.
+	///
+	/// # Arguments
+	/// * 'pattern' - The pattern to stamp.
+	/// * 'at'      - The cell under the top left corner of the pattern.
+	pub fn stamp(&mut self, pattern: &Pattern, at: (i32, i32)) {
+		for (&(x, y), &state) in pattern.cells().iter().zip(pattern.states().iter()) {
+			self.set_state(at.0 + x, at.1 + y, state);
+		}
+	}
+
+	/// Cuts a block of cells down to the part on the map.
+	///
+	/// # Arguments
+	/// * 'rect' - The block as (x, y, width, height).
+	///
+	/// # Return
+	/// * Option<(i32, i32, i32, i32)> - The part on the map, None if there is none.
+	pub fn clip(&self, rect: (i32, i32, i32, i32)) -> Option<(i32, i32, i32, i32)> {
+		let (width, height) = self.size();
+		let x0 = rect.0.max(0);
+		let y0 = rect.1.max(0);
+		let x1 = (rect.0 + rect.2).min(width);
+		let y1 = (rect.1 + rect.3).min(height);
+		if x1 > x0 && y1 > y0 { Some((x0, y0, x1 - x0, y1 - y0)) } else { None }
+	}
+
+	/// Copies the cells in a block that aren't dead into a pattern, under the world's rule.
+	/// The block is clipped to the map first.
+	///
+	/// # Arguments
+	/// * 'rect' - The block as (x, y, width, height).
+	///
+	/// # Return
+	/// * Option<Pattern> - The pattern, the size of the clipped block. None if the block is off the map.
+	pub fn copy(&self, rect: (i32, i32, i32, i32)) -> Option<Pattern> {
+		self.clip(rect).map(|(x0, y0, width, height)| {
+			let mut cells = Vec::new();
+			for y in 0..height {
+				for x in 0..width {
+					match self.map[(y0 + y) as usize][(x0 + x) as usize] {
+						DEAD => (),
+						state => cells.push((x, y, state)),
+					}
+				}
+			}
+			Pattern::with_states("Selection", (width, height), cells, Some(self.rule.to_string()))
+		})
+	}
+
+	/// Sets every cell in a block, keeping the count of living cells. Dying cells are set too.
+	/// Cells off the map are ignored.
+	///
+	/// # Arguments
+	/// * 'rect'  - The block as (x, y, width, height).
+	/// * 'alive' - The new state of the cells.
+	pub fn fill(&mut self, rect: (i32, i32, i32, i32), alive: bool) {
+		if let Some((x0, y0, width, height)) = self.clip(rect) {
+			for y in y0..y0 + height {
+				for x in x0..x0 + width {
+					self.set_cell(x, y, alive);
+				}
+			}
+		}
+	}
+
+	/// Replaces every cell in a block with a random state. Cells off the map are ignored.
+	///
+	/// # Arguments
+	/// * 'rect'    - The block as (x, y, width, height).
+	/// * 'density' - The chance of each cell being alive, from 0 to 1.
+	/// * 'seed'    - Seed for the states. None picks a random seed.
+	pub fn scatter(&mut self, rect: (i32, i32, i32, i32), density: f64, seed: Option<u64>) {
+		let mut range = seeded(seed);
+		let density = density.clamp(0.0, 1.0);
+		if let Some((x0, y0, width, height)) = self.clip(rect) {
+			for y in y0..y0 + height {
+				for x in x0..x0 + width {
+					let alive = range.gen_bool(density);
+					self.set_cell(x, y, alive);
+				}
+			}
+		}
+	}
+
+	/// Lifts the cells in a block out as a pattern, changes it, and puts it back with the
+	/// same top left corner. Cells that end up off the map are dropped.
+	///
+	/// # Arguments
+	/// * 'rect'   - The block as (x, y, width, height).
+	/// * 'change' - Changes the pattern, such as by rotating it.
+	///
+	/// # Return
+	/// * Option<(i32, i32, i32, i32)> - The block the changed pattern covers on the map, None if there is none.
+	pub fn transform<F: FnOnce(&mut Pattern)>(&mut self, rect: (i32, i32, i32, i32), change: F) -> Option<(i32, i32, i32, i32)> {
+		let mut pattern = self.copy(rect)?;
+		let rect = self.clip(rect)?;
+		self.fill(rect, false);
+		change(&mut pattern);
+		self.stamp(&pattern, (rect.0, rect.1));
+		let (width, height) = pattern.size();
+		self.clip((rect.0, rect.1, width, height))
+	}
+
+	/// Returns the world's meta data without updating the map.
+	///
+	/// # Return
+	/// * Stats - Counts of the current generation and every generation so far.
+	pub fn stats(&self) -> Stats {
+		let (width, height) = self.size();
+		let area = width as u64 * height as u64;
+		Stats {
+			generation: self.generation,
+			living: self.living,
+			births: self.changes.0,
+			deaths: self.changes.1,
+			changed: self.changes.0 + self.changes.1,
+			total_births: self.born,
+			total_deaths: self.dead,
+			peak: self.peak.0,
+			peak_generation: self.peak.1,
+			//Edits may have moved the edges, so look for them again
+			bounds: self.bounds.unwrap_or_else(|| self.bounds()),
+			density: if area > 0 { self.living as f64 / area as f64 } else { 0.0 },
+			colors: self.color_counts,
+		}
+	}
+
+	/// Updates cells in the map and returns the world's meta data.
 	/// Required function for ggez
 	///
 	/// # Return
-	/// * (i32, i32, i32) - Tripplet of:
-	///   * i32 - Current generation
-	///   * i32 - Current number of living cells
-	///   * i32 - Current total of living cells that died
-	pub fn update(&mut self) -> (i32, i32, i32) {
+	/// * Stats - Counts of the new generation and every generation so far.
+	pub fn update(&mut self) -> Stats {
 		self.clasic_generation();
-		(self.generation, self.living, self.dead)
+		let stats = self.stats();
+		self.write_stats(&stats);
+		stats
+	}
+
+	/// Starts or stops recording stats. A new recorder gets the current stats first,
+	/// so a recording starts from the generation it was switched on at.
+	///
+	/// # Arguments
+	/// * 'recorder' - Where to write the stats of each generation, None to stop. Any old recorder is flushed.
+	pub fn set_recorder(&mut self, recorder: Option<Recorder>) {
+		self.recorder = recorder;
+		let stats = self.stats();
+		self.write_stats(&stats);
+	}
+
+	/// Stops recording stats, handing back the recorder so it can carry on with another world
+	pub fn take_recorder(&mut self) -> Option<Recorder> {
+		self.recorder.take()
+	}
+
+	/// Writes stats to the recorder, if there is one. A recorder that fails is dropped
+	/// rather than failing again every generation.
+	fn write_stats(&mut self, stats: &Stats) {
+		let failed = match self.recorder {
+			Some(ref mut recorder) => recorder.write(stats).err(),
+			None => None,
+		};
+		if let Some(error) = failed {
+			eprintln!("Error recording stats: {}", error);
+			self.recorder = None;
+		}
+	}
+
+	/// Checks for cells that are neither alive nor at rest: dying cells, or electron tails under WireWorld.
+	/// Dead cells and WireWorld conductors are at rest.
+	///
+	/// # Return
+	/// * bool - True if any cell is still dying.
+	fn any_dying(&self) -> bool {
+		let rest = if self.rule.is_wireworld() { CONDUCTOR } else { DEAD };
+		self.map.iter().any(|row| row.iter().any(|&state| state != ALIVE && state != DEAD && state != rest))
+	}
+
+	/// Returns a hash of the map. Maps with the same living cells have the same fingerprint.
+	pub fn fingerprint(&self) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		self.map.hash(&mut hasher);
+		hasher.finish()
+	}
+
+	/// Passes draw command to the living cells the camera can see, colored by player under rules with colors or by age if ages are tracked,
+	/// and dying cells, faded by how near to dead they are, along with the overlays shown. The heatmap and trails go under the living cells, and changes over them.
+	/// Zoomed out far enough that cells are smaller than pixels, each pixel is shaded by how many of its
+	/// cells are alive, without overlays.
+	///
+	/// # Arguments
+	/// * 'renderer' - The backend to draw with.
+	/// * 'camera'   - Decides which cells are drawn where.
+	pub fn draw(&self, renderer: &mut dyn Renderer, camera: &Camera) {
+		let (width, height) = self.size();
+		let zoom = camera.zoom();
+		let hex = self.rule.neighborhood().is_hex();
+
+		//Shade the world so its edges show against the window, taking in the half cell odd hex rows stick out
+		let (left, top) = camera.to_screen(0.0, 0.0);
+		let extra = if hex { 0.5 } else { 0.0 };
+		if let Some((x, y, w, h)) = camera.clip((left, top, (width as f32 + extra) * zoom, height as f32 * zoom)) {
+			renderer.set_color(COLOR_WORLD);
+			renderer.fill_rect(x, y, w, h);
+		}
+
+		//Only loop over cells in view, and hex cells reaching into view from just outside
+		let (x0, y0, x1, y1) = camera.visible();
+		let reach = if hex { 1 } else { 0 };
+		let visible = ((x0 - reach).max(0), (y0 - reach).max(0), (x1 + reach).min(width), (y1 + reach).min(height));
+
+		if zoom < 1.0 {
+			self.draw_density(renderer, camera, visible);
+			return;
+		}
+
+		if let Some((ref heat, hottest)) = self.heat {
+			let colors = Gradient::new(HEAT_COLORS.to_vec());
+			self.draw_cells(renderer, camera, visible, |x, y| match heat[y][x] {
+				0 => None,
+				count => Some(colors.color((count as u64 * 1000 / hottest.max(1) as u64) as u32)),
+			});
+		}
+		if let Some(ref trails) = self.trails {
+			let colors = Gradient::new(TRAIL_COLORS.to_vec());
+			self.draw_cells(renderer, camera, visible, |x, y| match trails[y][x] {
+				0 => None,
+				left => Some(colors.color((TRAIL_LENGTH - left + 1) as u32)),
+			});
+		}
+
+		//Draw living and dying cells, or the parts of WireWorld circuits
+		let dying = self.dying_colors();
+		self.draw_cells(renderer, camera, visible, |x, y| self.cell_color(x, y, &dying));
+
+		if let Some(ref previous) = self.previous {
+			self.draw_cells(renderer, camera, visible, |x, y| match (previous[y][x] == ALIVE, self.map[y][x] == ALIVE) {
+				(false, true) => Some(COLOR_BORN),
+				(true, false) => Some(COLOR_DIED),
+				_ => None,
+			});
+		}
+	}
+
+	/// Returns the colors dying cells fade through, from just dying to nearly dead
+	fn dying_colors(&self) -> Gradient {
+		Gradient::new(vec![(2, COLOR_DYING), ((self.rule.states() - 1).max(3) as u32, COLOR_NEARLY_DEAD)])
+	}
+
+	/// Picks the color a cell is drawn in: by player under rules with colors, by age if ages
+	/// are tracked, faded if dying, or by its part of a WireWorld circuit.
+	///
+	/// # Arguments
+	/// * 'x & y' - The cell.
+	/// * 'dying' - The colors dying cells fade through.
+	///
+	/// # Return
+	/// * Option<Color> - The color, None for a dead cell.
+	fn cell_color(&self, x: usize, y: usize, dying: &Gradient) -> Option<Color> {
+		let wireworld = self.rule.is_wireworld();
+		match self.map[y][x] {
+			DEAD => None,
+			HEAD if wireworld => Some(COLOR_HEAD),
+			TAIL if wireworld => Some(COLOR_TAIL),
+			_ if wireworld => Some(COLOR_CONDUCTOR),
+			ALIVE => Some(match (self.colors.as_ref(), self.ages.as_ref()) {
+				(Some(colors), _) => PLAYER_COLORS[colors[y][x] as usize],
+				(None, Some(ages)) => self.gradient.color(ages[y][x]),
+				(None, None) => COLOR_CELL,
+			}),
+			state => Some(dying.color(state as u32)),
+		}
 	}
 
-	/// Passes draw command to living cells. Used to [draw in ggez](https://docs.rs/ggez/0.4.1/ggez/graphics/fn.draw.html).
+	/// Draws the whole map as a picture for saving, a square of pixels for each cell on the
+	/// world's background. Hex grids are drawn as squares, like a one dimensional diagram.
 	///
 	/// # Arguments
-	/// * 'ctx' - [ggez global resources](https://docs.rs/ggez/0.3.1/ggez/struct.Context.html). 
-	pub fn draw(&mut self, ctx: &mut Context) {
-		let topix = |x:i32| { ((x * SIZE_GRID_PIXELS) as i32) };
-		let boarder = 1;
+	/// * 'scale' - Pixels on a side of each cell.
+	///
+	/// # Return
+	/// * (u32, u32, Vec<u8>) - The width and height of the picture, then four bytes of color per pixel.
+	pub fn image(&self, scale: i32) -> (u32, u32, Vec<u8>) {
+		let (width, height) = self.size();
+		let scale = scale.max(1) as usize;
+		let dying = self.dying_colors();
+		//Cells are see-through, so lay them over the background
+		let byte = |value: f32| { (value * 255.0).round() as u8 };
+		let blend = |color: Color| {
+			let mix = |i: usize| byte(color[i] * color[3] + COLOR_WORLD[i] * (1.0 - color[3]));
+			[mix(0), mix(1), mix(2), 255]
+		};
+
+		let mut rgba = Vec::with_capacity(width as usize * height as usize * scale * scale * 4);
+		for y in 0..height as usize {
+			let row: Vec<[u8; 4]> = (0..width as usize).map(|x| blend(self.cell_color(x, y, &dying).unwrap_or(COLOR_WORLD))).collect();
+			for _ in 0..scale {
+				for pixel in row.iter() {
+					for _ in 0..scale {
+						rgba.extend_from_slice(pixel);
+					}
+				}
+			}
+		}
+		(width as u32 * scale as u32, height as u32 * scale as u32, rgba)
+	}
 
-		//Loop over cells in the map
+	/// Draws a square, or a hexagon on a hex grid, over each cell in view that is given a color.
+	///
+	/// # Arguments
+	/// * 'renderer' - The backend to draw with.
+	/// * 'camera'   - Decides which cells are drawn where.
+	/// * 'visible'  - The cells in view as (x0, y0, x1, y1), with x1 and y1 excluded.
+	/// * 'color_of' - Picks the color of the cell at (x, y), None to leave it out.
+	fn draw_cells<F: Fn(usize, usize) -> Option<Color>>(&self, renderer: &mut dyn Renderer, camera: &Camera, visible: (i32, i32, i32, i32), color_of: F) {
+		let zoom = camera.zoom();
+		//Cells big enough to see get a boarder to tell neighbors apart
+		let boarder = if zoom >= 3.0 { 1.0 } else { 0.0 };
+		let hex = self.rule.neighborhood().is_hex();
+		let mut current = None;
+		for y in visible.1..visible.3 {
+			for x in visible.0..visible.2 {
+				if let Some(color) = color_of(x as usize, y as usize) {
+					//Only change color when the cell calls for a different one
+					if current != Some(color) {
+						current = Some(color);
+						renderer.set_color(color);
+					}
+					if hex {
+						//Shrink the hexagon toward its middle to leave the boarder
+						let (cx, cy) = hex_middle(x, y);
+						let scale = (zoom - boarder) / zoom;
+						let corners: Vec<(f32, f32)> = HEX_CORNERS.iter()
+							.map(|&(dx, dy)| camera.to_screen(cx + dx * scale, cy + dy * scale))
+							.collect();
+						let corners = camera.clip_polygon(&corners);
+						if corners.len() >= 3 {
+							renderer.fill_polygon(&corners);
+						}
+						continue;
+					}
+					let (px, py) = camera.to_screen(x as f32, y as f32);
+					if let Some((px, py, w, h)) = camera.clip((px, py, zoom - boarder, zoom - boarder)) {
+						renderer.fill_rect(px, py, w, h);
+					}
+				}
+			}
+		}
+	}
+
+	/// Draws cells smaller than a pixel as one image, shading each pixel by the share of its cells that are alive.
+	///
+	/// # Arguments
+	/// * 'renderer' - The backend to draw with.
+	/// * 'camera'   - Decides which cells are drawn where.
+	/// * 'visible'  - The cells in view as (x0, y0, x1, y1), with x1 and y1 excluded.
+	fn draw_density(&self, renderer: &mut dyn Renderer, camera: &Camera, visible: (i32, i32, i32, i32)) {
+		let (vx, vy, vw, vh) = camera.viewport();
+		let (width, height) = (vw as usize, vh as usize);
+		let zoom = camera.zoom();
+
+		//Count the living cells under each pixel
+		let mut counts = vec![0u32; width * height];
+		for y in visible.1..visible.3 {
+			for x in visible.0..visible.2 {
+				if self.map[y as usize][x as usize] == ALIVE {
+					let (px, py) = camera.to_screen(x as f32, y as f32);
+					let (px, py) = ((px - vx).floor(), (py - vy).floor());
+					if px >= 0.0 && py >= 0.0 && (px as usize) < width && (py as usize) < height {
+						counts[py as usize * width + px as usize] += 1;
+					}
+				}
+			}
+		}
+
+		//Each pixel covers 1/zoom² cells
+		let rgba = shade(counts, 1.0 / (zoom * zoom));
+		renderer.pixels(vx, vy, width as u16, height as u16, &rgba, 1.0);
+	}
+
+	/// Draws a miniature of the whole map, each pixel shaded by the share of its cells that are alive.
+	///
+	/// # Arguments
+	/// * 'max_size' - The most pixels the miniature may have on a side.
+	///
+	/// # Return
+	/// * (u16, u16, Vec<u8>) - The width and height of the miniature, then four bytes of color per pixel.
+	pub fn thumbnail(&self, max_size: i32) -> (u16, u16, Vec<u8>) {
+		let (width, height) = self.size();
+
+		//Each pixel covers a square block of cells
+		let block = ((width.max(height) + max_size - 1) / max_size).max(1);
+		let (thumb_width, thumb_height) = ((width + block - 1) / block, (height + block - 1) / block);
+
+		let mut counts = vec![0u32; (thumb_width * thumb_height) as usize];
 		for (y, row) in self.map.iter().enumerate() {
-			for (x, life) in row.iter().enumerate() {
-				//Draw living cells
-				match life {
-					true => {
-						//set_color(r: f32, g: f32, b: f32 a: f32)
-						graphics::set_color(ctx, [0.5, 0.5, 0.5, 0.9].into()).expect("Error setting color"); 
-						graphics::rectangle(ctx, 
-							graphics::DrawMode::Fill,
-							//Rect(x: f32, y: f32, w: f32, h: f32)
-							graphics::Rect::new_i32 (
-								topix(x as i32), 
-								topix(y as i32), 
-								topix(1)-boarder, 
-								topix(1)-boarder,
-							)
-						).expect("Error drawing Rect");
-					},
-					false => (),
+			for (x, state) in row.iter().enumerate() {
+				if *state == ALIVE {
+					counts[((y as i32 / block) * thumb_width + x as i32 / block) as usize] += 1;
 				}
 			}
 		}
+		(thumb_width as u16, thumb_height as u16, shade(counts, (block * block) as f32))
+	}
+}
+
+/// Generates a random number generator from a seed.
+///
+/// # Arguments
+/// * 'seed' - The seed. The same seed always gives the same numbers. None picks a random seed.
+fn seeded(seed: Option<u64>) -> StdRng {
+	match seed {
+		Some(seed) => {
+			let mut bytes = [0u8; 32];
+			for (i, byte) in bytes.iter_mut().enumerate() {
+				*byte = (seed >> ((i % 8) * 8)) as u8;
+			}
+			StdRng::from_seed(bytes)
+		},
+		None => StdRng::from_rng(rand::thread_rng()).expect("Error seeding world"),
+	}
+}
+
+/// Finds the middle of a hex cell in world coordinates. Odd rows sit half a cell right of even rows.
+///
+/// # Arguments
+/// * 'x & y' - The cell.
+fn hex_middle(x: i32, y: i32) -> (f32, f32) {
+	let shift = if y & 1 == 1 { 0.5 } else { 0.0 };
+	(x as f32 + 0.5 + shift, y as f32 + 0.5)
+}
+
+/// Writes a row into rows laid out like the map, as a one dimensional generation does:
+/// below the latest row, or at the bottom once the rows above are scrolled up.
+///
+/// # Arguments
+/// * 'rows'   - The rows to write into.
+/// * 'latest' - The latest row written.
+/// * 'row'    - The new row.
+///
+/// # Return
+/// * Vec<T> - The row written over, or the row scrolled off the top.
+fn put_row<T>(rows: &mut Vec<Vec<T>>, latest: usize, row: Vec<T>) -> Vec<T> {
+	if latest + 1 < rows.len() {
+		mem::replace(&mut rows[latest + 1], row)
+	}
+	else {
+		rows.push(row);
+		rows.remove(0)
+	}
+}
+
+/// Colors pixels by how many living cells they cover.
+///
+/// # Arguments
+/// * 'counts'          - The number of living cells under each pixel.
+/// * 'cells_per_pixel' - The number of cells under each pixel.
+///
+/// # Return
+/// * Vec<u8> - Four bytes of color per pixel, full strength where every cell is alive.
+fn shade(counts: Vec<u32>, cells_per_pixel: f32) -> Vec<u8> {
+	let byte = |value: f32| { (value * 255.0) as u8 };
+	let mut rgba = Vec::with_capacity(counts.len() * 4);
+	for count in counts {
+		let density = (count as f32 / cells_per_pixel).min(1.0);
+		rgba.extend_from_slice(&[byte(COLOR_CELL[0]), byte(COLOR_CELL[1]), byte(COLOR_CELL[2]), byte(density * COLOR_CELL[3])]);
+	}
+	rgba
+}
+
+/// Counts the living neighbors of every cell within a range. Squares are counted with a summed-area
+/// table in the same time whatever the radius, and diamonds with a sum of each row, in time that
+/// only grows with the radius.
+///
+/// # Arguments
+/// * 'map'    - The map of cells.
+/// * 'radius' - How far the range reaches from each cell.
+/// * 'range'  - The shape of the range.
+/// * 'middle' - True if living cells count themselves.
+///
+/// # Return
+/// * Vec<Vec<i32>> - The count for each cell of the map.
+fn range_counts(map: &[Vec<State>], radius: i32, range: Range, middle: bool) -> Vec<Vec<i32>> {
+	let height = map.len() as i32;
+	let width = map.first().map_or(0, |row| row.len()) as i32;
+
+	//Living cells in each row, left of each column
+	let mut rows = vec![vec![0i32; width as usize + 1]; height as usize];
+	for (y, row) in map.iter().enumerate() {
+		for (x, state) in row.iter().enumerate() {
+			rows[y][x + 1] = rows[y][x] + if *state == ALIVE { 1 } else { 0 };
+		}
+	}
+
+	//Living cells above and left of each corner
+	let mut table = vec![vec![0i32; width as usize + 1]; height as usize + 1];
+	if range == Range::Moore {
+		for y in 0..height as usize {
+			for x in 0..width as usize + 1 {
+				table[y + 1][x] = table[y][x] + rows[y][x];
+			}
+		}
+	}
+
+	let mut counts = vec![vec![0i32; width as usize]; height as usize];
+	for y in 0..height {
+		for x in 0..width {
+			let mut count = match range {
+				Range::Moore => {
+					let (x0, x1) = ((x - radius).max(0) as usize, (x + radius + 1).min(width) as usize);
+					let (y0, y1) = ((y - radius).max(0) as usize, (y + radius + 1).min(height) as usize);
+					table[y1][x1] - table[y0][x1] - table[y1][x0] + table[y0][x0]
+				},
+				Range::VonNeumann => {
+					let mut count = 0;
+					for row in (y - radius).max(0)..(y + radius + 1).min(height) {
+						let reach = radius - (row - y).abs();
+						let (x0, x1) = ((x - reach).max(0) as usize, (x + reach + 1).min(width) as usize);
+						count += rows[row as usize][x1] - rows[row as usize][x0];
+					}
+					count
+				},
+			};
+			if !middle && map[y as usize][x as usize] == ALIVE {
+				count -= 1;
+			}
+			counts[y as usize][x as usize] = count;
+		}
+	}
+	counts
+}
+
+///Longest cycle a StabilityWatch can notice
+const MAX_CYCLE_PERIOD: usize = 1000;
+
+///How a world stopped changing
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Settled {
+	///No living or dying cells are left
+	Extinct,
+	///The map repeats every so many generations, 1 for a still life
+	Cycle(u64),
+}
+
+impl fmt::Display for Settled {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Settled::Extinct => write!(f, "extinct"),
+			Settled::Cycle(1) => write!(f, "still"),
+			Settled::Cycle(period) => write!(f, "period {}", period),
+		}
+	}
+}
+
+///Remembers a world's recent maps to notice when it dies out or starts repeating
+pub struct StabilityWatch {
+	///Fingerprint of each recent map, with the generation it was seen at
+	seen: HashMap<u64, u64>,
+	///Fingerprints in the order they were seen, so the oldest can be forgotten
+	order: VecDeque<u64>,
+}
+
+impl StabilityWatch {
+	/// Generates a watch that hasn't seen any maps yet
+	pub fn new() -> Self {
+		StabilityWatch { seen: HashMap::new(), order: VecDeque::new() }
+	}
+
+	/// Checks a world's current map against the maps seen before it.
+	///
+	/// # Arguments
+	/// * 'world' - The world, checked once each generation.
+	///
+	/// # Return
+	/// * Option<Settled> - How the world settled, None while it is still changing.
+	pub fn check(&mut self, world: &World) -> Option<Settled> {
+		//Dying cells still on the map fade out first
+		if world.living == 0 && !world.any_dying() {
+			return Some(Settled::Extinct);
+		}
+
+		let fingerprint = world.fingerprint();
+		if let Some(generation) = self.seen.get(&fingerprint) {
+			return Some(Settled::Cycle(world.generation - generation));
+		}
+
+		self.seen.insert(fingerprint, world.generation);
+		self.order.push_back(fingerprint);
+		if self.order.len() > MAX_CYCLE_PERIOD {
+			let oldest = self.order.pop_front().expect("Error forgetting map");
+			self.seen.remove(&oldest);
+		}
+		None
 	}
 }
 
@@ -237,7 +1422,8 @@ fn test_gol_update_and_classic_generation() {
 	//update causes a generation, and since the map only has one life it should kill it, 
 	//leaving no living and one dead
 	let mut world = World::new(1);
-	assert_eq!((1,0,1), world.update());
+	let stats = world.update();
+	assert_eq!((1, 0, 1), (stats.generation, stats.living, stats.total_deaths));
 
 	//Three live cells in a row causes a blinker structure which kills two cells each generation and creates two living cells.
 	//For each update, generation should go up by one, living should stay the same, and dead should go up by two.
@@ -246,17 +1432,35 @@ fn test_gol_update_and_classic_generation() {
 	locals [1][1] = true;
 	locals [1][2] = true;
 	world = World::set_all(locals, 0, 3, 0);
-	assert_eq!((1,3,2), world.update());
-	assert_eq!((2,3,4), world.update());
+	let stats = world.update();
+	assert_eq!((1, 3, 2), (stats.generation, stats.living, stats.total_deaths));
+	let stats = world.update();
+	assert_eq!((2, 3, 4), (stats.generation, stats.living, stats.total_deaths));
+
+	//Counts of the last generation sit beside running totals
+	assert_eq!((2, 2, 4), (stats.births, stats.deaths, stats.changed));
+	assert_eq!((4, 4), (stats.total_births, stats.total_deaths));
+	assert_eq!((3, 0), (stats.peak, stats.peak_generation));
+	assert_eq!(Some((0, 1, 3, 1)), stats.bounds);
+	assert_eq!(3.0 / (WIDTH_GAME_GRIDS * HEIGHT_GAME_GRIDS) as f64, stats.density);
+
+	//Edits move the peak and bounds too
+	world.set_line((5, 5), (8, 5), true);
+	let stats = world.stats();
+	assert_eq!((7, 2), (stats.peak, stats.peak_generation));
+	assert_eq!(Some((0, 1, 9, 5)), stats.bounds);
+	world.set_line((5, 5), (8, 5), false);
+	assert_eq!(Some((0, 1, 3, 1)), world.stats().bounds);
+	assert_eq!(7, world.stats().peak);
 }
 
 #[test]
 fn test_gol_num_neighbors() {
-	let mut locals: Vec<Vec<bool>> = vec![vec![false; WIDTH_GAME_GRIDS as usize]; HEIGHT_GAME_GRIDS as usize];
+	let mut locals: Vec<Vec<State>> = vec![vec![DEAD; WIDTH_GAME_GRIDS as usize]; HEIGHT_GAME_GRIDS as usize];
 	for y in 0..HEIGHT_GAME_GRIDS {
 		for x in 0..WIDTH_GAME_GRIDS {
 			
-			locals [y as usize][x as usize] = true;
+			locals [y as usize][x as usize] = ALIVE;
 
 			if y == 0 {
 				//top left corner
@@ -341,7 +1545,677 @@ fn test_gol_num_neighbors() {
 				}
 			}
 
-			locals [y as usize][x as usize] = false;
+			locals [y as usize][x as usize] = DEAD;
 		}
 	}
-} 
\ No newline at end of file
+} 
+
+#[test]
+fn test_gol_draw() {
+	use render::{DrawCall, MemoryRenderer};
+	use params::SIZE_GRID_PIXELS;
+
+	let mut locals: Vec<Vec<bool>> = vec![vec![false; WIDTH_GAME_GRIDS as usize]; HEIGHT_GAME_GRIDS as usize];
+	locals [2][1] = true;
+	locals [3][4] = true;
+	let world = World::set_all(locals, 0, 2, 0);
+
+	let area = ((WIDTH_GAME_GRIDS * SIZE_GRID_PIXELS) as f32, (HEIGHT_GAME_GRIDS * SIZE_GRID_PIXELS) as f32);
+	let mut camera = Camera::new((0.0, 0.0, area.0, area.1));
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+
+	//The world behind, then one rect per living cell, one grid square each, less the boarder
+	let size = (SIZE_GRID_PIXELS - 1) as f32;
+	assert_eq!(vec![
+		(COLOR_WORLD, (0.0, 0.0, area.0, area.1)),
+		(COLOR_CELL, (SIZE_GRID_PIXELS as f32, (2 * SIZE_GRID_PIXELS) as f32, size, size)),
+		(COLOR_CELL, ((4 * SIZE_GRID_PIXELS) as f32, (3 * SIZE_GRID_PIXELS) as f32, size, size)),
+	], renderer.rects());
+
+	//Cells off camera aren't drawn, and cells on the edge are cut to fit
+	camera.pan(-1.5 * SIZE_GRID_PIXELS as f32, 0.0);
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+	assert_eq!(vec![
+		(COLOR_WORLD, (0.0, 0.0, area.0 - 1.5 * SIZE_GRID_PIXELS as f32, area.1)),
+		(COLOR_CELL, (0.0, (2 * SIZE_GRID_PIXELS) as f32, size - 0.5 * SIZE_GRID_PIXELS as f32, size)),
+		(COLOR_CELL, (2.5 * SIZE_GRID_PIXELS as f32, (3 * SIZE_GRID_PIXELS) as f32, size, size)),
+	], renderer.rects());
+
+	//Tracking ages colors each cell by its age
+	let mut world = world;
+	world.track_ages(true);
+	world.set_gradient(Gradient::new(vec![(1, [1.0, 0.0, 0.0, 1.0]), (3, [0.0, 0.0, 1.0, 1.0])]));
+	world.set_cell(4, 3, false);
+	world.set_cell(5, 3, true);
+	world.ages.as_mut().unwrap()[2][1] = 2;
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+	assert_eq!(vec![
+		([0.5, 0.0, 0.5, 1.0], (0.0, (2 * SIZE_GRID_PIXELS) as f32, size - 0.5 * SIZE_GRID_PIXELS as f32, size)),
+		([1.0, 0.0, 0.0, 1.0], (3.5 * SIZE_GRID_PIXELS as f32, (3 * SIZE_GRID_PIXELS) as f32, size, size)),
+	], renderer.rects()[1..].to_vec());
+
+	//Zoomed out to four cells a pixel, a solid 4x4 block is one solid pixel
+	let mut locals: Vec<Vec<bool>> = vec![vec![false; 8]; 8];
+	for row in locals.iter_mut().take(4) {
+		for cell in row.iter_mut().take(4) {
+			*cell = true;
+		}
+	}
+	let world = World::set_all(locals, 0, 16, 0);
+	let mut camera = Camera::new((0.0, 0.0, 4.0, 4.0));
+	camera.zoom_at(0.0, 0.0, 0.25 / SIZE_GRID_PIXELS as f32);
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+	match renderer.calls().last() {
+		Some(&DrawCall::Pixels(0.0, 0.0, 4, 4, ref rgba, 1.0)) => {
+			assert_eq!(229, rgba[3]);
+			assert!(rgba.iter().skip(7).step_by(4).all(|alpha| *alpha == 0));
+		},
+		call => panic!("Expected pixels, found {:?}", call),
+	}
+}
+
+#[test]
+fn test_gol_edit_cells() {
+	let locals: Vec<Vec<bool>> = vec![vec![false; WIDTH_GAME_GRIDS as usize]; HEIGHT_GAME_GRIDS as usize];
+	let mut world = World::set_all(locals, 0, 0, 0);
+
+	//Pixels map to the cell drawn under them, through the camera
+	use params::SIZE_GRID_PIXELS;
+	let mut camera = Camera::new((0.0, 0.0, 1000.0, 1000.0));
+	assert_eq!(Some((0, 0)), world.grid_at(&camera, 0, 0));
+	assert_eq!(Some((2, 3)), world.grid_at(&camera, 2 * SIZE_GRID_PIXELS + 1, 3 * SIZE_GRID_PIXELS + SIZE_GRID_PIXELS - 1));
+	assert_eq!(None, world.grid_at(&camera, WIDTH_GAME_GRIDS * SIZE_GRID_PIXELS, 0));
+	assert_eq!(None, world.grid_at(&camera, -1, 0));
+	camera.pan(SIZE_GRID_PIXELS as f32, 0.0);
+	assert_eq!(None, world.grid_at(&camera, 0, 0));
+	assert_eq!(Some((0, 0)), world.grid_at(&camera, SIZE_GRID_PIXELS, 0));
+
+	//Living count follows every edit, and repeated edits change nothing
+	assert!(world.set_cell(1, 1, true));
+	assert!(!world.set_cell(1, 1, true));
+	assert!(!world.set_cell(-1, WIDTH_GAME_GRIDS, true));
+	assert_eq!(1, world.stats().living);
+	world.toggle_cell(1, 1);
+	world.toggle_cell(2, 2);
+	assert_eq!(Some(false), world.get_cell(1, 1));
+	assert_eq!(1, world.stats().living);
+
+	//A painted line has no gaps and doesn't count cells that were already alive
+	world.set_line((0, 2), (4, 2), true);
+	assert_eq!(5, world.stats().living);
+	world.set_line((4, 0), (0, 4), true);
+	for i in 0..5 {
+		assert_eq!(Some(true), world.get_cell(4 - i, i));
+	}
+	assert_eq!(9, world.stats().living);
+	world.set_line((0, 2), (4, 2), false);
+	assert_eq!(4, world.stats().living);
+	assert_eq!(Some((0, 0, 5, 5)), world.bounds());
+
+	//Thumbnails shrink the map in whole blocks, shading each block by how full it is
+	let (width, height, rgba) = world.thumbnail(HEIGHT_GAME_GRIDS / 2);
+	assert_eq!(((WIDTH_GAME_GRIDS / 2) as u16, (HEIGHT_GAME_GRIDS / 2) as u16), (width, height));
+	assert_eq!(0, rgba[3]);
+	assert_eq!((0.25 * COLOR_CELL[3] * 255.0) as u8, rgba[4 * 2 + 3]);
+	assert_eq!(None, World::set_all(vec![vec![false; 3]; 3], 0, 0, 0).bounds());
+
+	//Stamping a glider over the corner keeps the cells that land on the map
+	let glider = Pattern::parse_rle("glider", "bo$2bo$3o!").unwrap();
+	let mut world = World::generate(0, (10, 10), None);
+	world.stamp(&glider, (-1, 8));
+	assert_eq!(Some((0, 8, 2, 2)), world.bounds());
+	assert_eq!(2, world.stats().living);
+}
+
+#[test]
+fn test_gol_edit_blocks() {
+	let glider = Pattern::parse_rle("glider", "bo$2bo$3o!").unwrap();
+	let mut world = World::generate(0, (10, 10), None);
+	world.stamp(&glider, (0, 0));
+
+	//Blocks reaching past the edge are cut down to the map
+	assert_eq!(Some((8, 0, 2, 3)), world.clip((8, -2, 5, 5)));
+	assert_eq!(None, world.clip((10, 0, 3, 3)));
+	let copy = world.copy((-2, -2, 4, 4)).unwrap();
+	assert_eq!((2, 2), copy.size());
+	assert_eq!(&vec![(1, 0)], copy.cells());
+	assert_eq!(None, world.copy((-5, 0, 5, 5)));
+
+	//Rotating a block in place keeps its top left corner and drops cells that turn off the map
+	assert_eq!(Some((0, 0, 3, 3)), world.transform((0, 0, 3, 3), |pattern| pattern.rotate()));
+	assert_eq!(5, world.copy((0, 0, 3, 3)).unwrap().cells().len());
+	let mut line = World::generate(0, (10, 10), None);
+	line.fill((5, 8, 4, 1), true);
+	assert_eq!(Some((5, 8, 1, 2)), line.transform((5, 8, 4, 1), |pattern| pattern.rotate()));
+	assert_eq!(2, line.stats().living);
+
+	//Filling keeps the living count, and the same seed scatters the same cells
+	world.fill((0, 0, 10, 2), false);
+	assert_eq!(Some((0, 2, 2, 1)), world.bounds());
+	world.fill((0, 0, 10, 10), false);
+	assert_eq!(0, world.stats().living);
+	world.scatter((5, 5, 10, 10), 0.5, Some(7));
+	let mut again = World::generate(0, (10, 10), None);
+	again.scatter((5, 5, 5, 5), 0.5, Some(7));
+	assert_eq!(world.fingerprint(), again.fingerprint());
+	assert_eq!(world.bounds().map(|(x, y, _, _)| x >= 5 && y >= 5), Some(true));
+	world.scatter((0, 0, 10, 10), 1.0, None);
+	assert_eq!(100, world.stats().living);
+}
+
+#[test]
+fn test_gol_generate() {
+	//A population can't be larger than the map and every member lands on its own cell
+	let world = World::generate(50, (10, 4), None);
+	assert_eq!((4, 10), (world.map.len(), world.map[0].len()));
+	assert_eq!(40, world.stats().living);
+
+	//The same seed gives the same world
+	let first = World::generate(30, (20, 20), Some(42));
+	let second = World::generate(30, (20, 20), Some(42));
+	assert!(first.map == second.map);
+	assert_eq!(30, first.map.iter().flat_map(|row| row.iter()).filter(|state| **state == ALIVE).count());
+
+	//A small map with another rule. B2/S never survives, so a domino becomes two dominos.
+	let mut world = World::generate(0, (6, 3), None);
+	world.set_rule(Rule::parse("B2/S").unwrap());
+	world.set_cell(2, 1, true);
+	world.set_cell(3, 1, true);
+	let stats = world.update();
+	assert_eq!((1, 4, 2), (stats.generation, stats.living, stats.total_deaths));
+	assert_eq!(Some(true), world.get_cell(2, 0));
+	assert_eq!(Some(true), world.get_cell(3, 2));
+	assert_eq!(Some(false), world.get_cell(2, 1));
+}
+
+#[test]
+fn test_gol_stability_watch() {
+	let empty = || vec![vec![false; 6]; 6];
+
+	//A blinker repeats every two generations
+	let mut locals = empty();
+	locals[2][1] = true;
+	locals[2][2] = true;
+	locals[2][3] = true;
+	let mut world = World::set_all(locals, 0, 3, 0);
+	let mut watch = StabilityWatch::new();
+	assert_eq!(None, watch.check(&world));
+	world.update();
+	assert_eq!(None, watch.check(&world));
+	world.update();
+	assert_eq!(Some(Settled::Cycle(2)), watch.check(&world));
+
+	//A block never changes
+	let mut locals = empty();
+	locals[1][1] = true;
+	locals[1][2] = true;
+	locals[2][1] = true;
+	locals[2][2] = true;
+	let mut world = World::set_all(locals, 0, 4, 0);
+	let mut watch = StabilityWatch::new();
+	assert_eq!(None, watch.check(&world));
+	world.update();
+	assert_eq!(Some(Settled::Cycle(1)), watch.check(&world));
+
+	//A lone cell dies out
+	let mut locals = empty();
+	locals[3][3] = true;
+	let mut world = World::set_all(locals, 0, 1, 0);
+	let mut watch = StabilityWatch::new();
+	assert_eq!(None, watch.check(&world));
+	world.update();
+	assert_eq!(Some(Settled::Extinct), watch.check(&world));
+
+	//Under Brian's Brain a lone cell is only extinct once it has finished dying
+	let mut locals = empty();
+	locals[3][3] = true;
+	let mut world = World::set_all(locals, 0, 1, 0);
+	world.set_rule(Rule::parse("B2/S/C3").unwrap());
+	let mut watch = StabilityWatch::new();
+	assert_eq!(None, watch.check(&world));
+	world.update();
+	assert_eq!((0, None), (world.stats().living, watch.check(&world)));
+	world.update();
+	assert_eq!(Some(Settled::Extinct), watch.check(&world));
+
+	//A WireWorld circuit with no electrons left is at rest
+	let mut world = World::generate(0, (6, 6), None);
+	world.set_rule(Rule::parse("WireWorld").unwrap());
+	world.set_state(1, 1, CONDUCTOR);
+	world.set_state(2, 1, TAIL);
+	let mut watch = StabilityWatch::new();
+	assert_eq!(None, watch.check(&world));
+	world.update();
+	assert_eq!(Some(Settled::Extinct), watch.check(&world));
+}
+
+#[test]
+fn test_gol_ages() {
+	let mut world = World::generate(0, (5, 5), None);
+	world.set_line((1, 2), (3, 2), true);
+	assert_eq!(None, world.age(2, 2));
+
+	//A blinker's middle cell ages every generation while its ends are born again each time
+	world.track_ages(true);
+	assert_eq!(Some(1), world.age(1, 2));
+	world.update();
+	assert_eq!((Some(2), Some(1), Some(0)), (world.age(2, 2), world.age(2, 1), world.age(1, 2)));
+	world.track_ages(true);
+	world.update();
+	assert_eq!((Some(3), Some(0), Some(1)), (world.age(2, 2), world.age(2, 1), world.age(1, 2)));
+
+	//Edited cells start again, and coordinates off the map have no age
+	world.set_cell(2, 2, false);
+	world.set_cell(2, 2, true);
+	assert_eq!(Some(1), world.age(2, 2));
+	assert_eq!(None, world.age(-1, 0));
+	assert_eq!(None, world.age(5, 0));
+	world.track_ages(false);
+	assert_eq!(None, world.age(2, 2));
+}
+
+#[test]
+fn test_gol_overlays() {
+	use render::{MemoryRenderer};
+	use params::SIZE_GRID_PIXELS;
+
+	let mut world = World::generate(0, (5, 5), None);
+	world.set_line((1, 2), (3, 2), true);
+	for overlay in OVERLAYS.iter() {
+		world.show_overlay(*overlay, true);
+	}
+
+	//A blinker turns, its ends leaving trails and the middle heating up
+	world.update();
+	world.update();
+	assert_eq!((0, 0, TRAIL_LENGTH), {
+		let trails = world.trails.as_ref().unwrap();
+		(trails[2][1], trails[2][2], trails[1][2])
+	});
+	world.update();
+	assert_eq!((TRAIL_LENGTH, 0, 0), {
+		let trails = world.trails.as_ref().unwrap();
+		(trails[2][1], trails[2][2], trails[1][2])
+	});
+	assert_eq!((4, 2, 2, 4), {
+		let &(ref heat, hottest) = world.heat.as_ref().unwrap();
+		(heat[2][2], heat[2][1], heat[1][2], hottest)
+	});
+
+	//Births and deaths show over the living cells, with trails and heat under them
+	let camera = Camera::new((0.0, 0.0, (5 * SIZE_GRID_PIXELS) as f32, (5 * SIZE_GRID_PIXELS) as f32));
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+	let colors: Vec<Color> = renderer.rects().iter().map(|rect| rect.0).collect();
+	let heat = Gradient::new(HEAT_COLORS.to_vec());
+	assert_eq!(vec![
+		COLOR_WORLD,
+		heat.color(500), heat.color(500), heat.color(1000), heat.color(500), heat.color(500),
+		Gradient::new(TRAIL_COLORS.to_vec()).color(1), Gradient::new(TRAIL_COLORS.to_vec()).color(1),
+		COLOR_CELL, COLOR_CELL, COLOR_CELL,
+		COLOR_BORN, COLOR_DIED, COLOR_DIED, COLOR_BORN,
+	], colors);
+
+	//Edits aren't shown as changes, and hidden overlays are dropped
+	world.set_cell(0, 0, true);
+	assert_eq!(Some(ALIVE), world.previous.as_ref().map(|previous| previous[0][0]));
+	world.show_overlay(Overlay::Changes, false);
+	world.show_overlay(Overlay::Heatmap, false);
+	assert!(world.previous.is_none() && world.heat.is_none() && world.trails.is_some());
+
+	//Trails fade a little each generation
+	world.update();
+	world.update();
+	assert_eq!(TRAIL_LENGTH - 1, world.trails.as_ref().unwrap()[0][0]);
+}
+
+#[test]
+fn test_gol_generations() {
+	//Brian's Brain's smallest spaceship, two living cells pushed along by the two dying behind them
+	let mut world = World::generate(0, (8, 8), None);
+	world.set_rule(Rule::parse("B2/S/C3").unwrap());
+	world.set_line((3, 4), (4, 4), true);
+	world.set_state(3, 5, 2);
+	world.set_state(4, 5, 2);
+	assert_eq!(2, world.stats().living);
+
+	//It moves up a cell every generation, every living cell dying behind it
+	for generation in 1..4 {
+		let stats = world.update();
+		let y = 4 - generation;
+		assert_eq!((2, 2, 2), (stats.living, stats.births, stats.deaths));
+		assert_eq!(Some((3, y, 2, 1)), stats.bounds);
+		assert_eq!((Some(ALIVE), Some(2), Some(DEAD)), (world.get_state(3, y), world.get_state(4, y + 1), world.get_state(3, y + 2)));
+	}
+
+	//A lone cell spends a generation dying, with no neighbors to bring it back
+	let mut world = World::generate(0, (3, 3), None);
+	world.set_rule(Rule::parse("B2/S/C3").unwrap());
+	world.set_cell(1, 1, true);
+	world.update();
+	assert_eq!((Some(2), Some(false), 0), (world.get_state(1, 1), world.get_cell(1, 1), world.stats().living));
+
+	//Dying cells are drawn in their own colors
+	use render::{MemoryRenderer};
+	use params::SIZE_GRID_PIXELS;
+	let camera = Camera::new((0.0, 0.0, (3 * SIZE_GRID_PIXELS) as f32, (3 * SIZE_GRID_PIXELS) as f32));
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+	assert_eq!(vec![COLOR_WORLD, COLOR_DYING], renderer.rects().iter().map(|rect| rect.0).collect::<Vec<Color>>());
+	world.update();
+	assert_eq!(Some(DEAD), world.get_state(1, 1));
+
+	//Edits note old states, so dying cells come back when undone
+	world.set_state(0, 0, 2);
+	world.record();
+	world.toggle_cell(0, 0);
+	assert_eq!(vec![(0, 0, 2, ALIVE)], world.take_changes());
+	world.fill((0, 0, 3, 3), false);
+	assert_eq!(0, world.stats().living);
+}
+
+#[test]
+fn test_gol_larger_than_life() {
+	let world = World::generate(150, (20, 15), Some(7));
+
+	//Counts match counting each neighbor one at a time, at the edges too
+	for &(radius, range, middle) in [(3, Range::Moore, false), (2, Range::VonNeumann, true), (4, Range::VonNeumann, false)].iter() {
+		let counts = range_counts(&world.map, radius, range, middle);
+		for y in 0..15i32 {
+			for x in 0..20i32 {
+				let mut count = 0;
+				for dy in -radius..radius + 1 {
+					for dx in -radius..radius + 1 {
+						let inside = range == Range::Moore || dx.abs() + dy.abs() <= radius;
+						if inside && (middle || dx != 0 || dy != 0) && world.get_cell(x + dx, y + dy) == Some(true) {
+							count += 1;
+						}
+					}
+				}
+				assert_eq!(count, counts[y as usize][x as usize], "{:?} at {}, {}", range, x, y);
+			}
+		}
+	}
+
+	//Counting the middle and surviving with one more is the classic rule again
+	let mut classic = World::generate(150, (20, 15), Some(7));
+	let mut larger = World::generate(150, (20, 15), Some(7));
+	larger.set_rule(Rule::parse("R1,C0,M1,S3..4,B3..3,NM").unwrap());
+	for _ in 0..10 {
+		classic.update();
+		larger.update();
+		assert_eq!(classic.fingerprint(), larger.fingerprint());
+	}
+
+	//A square of radius 2 with B/S so low that a lone cell fills its range
+	let mut world = World::generate(0, (9, 9), None);
+	world.set_rule(Rule::parse("R2,C0,M0,S0..24,B1..24,NM").unwrap());
+	world.set_cell(4, 4, true);
+	assert_eq!(Some((2, 2, 5, 5)), world.update().bounds);
+	assert_eq!(25, world.stats().living);
+}
+
+#[test]
+fn test_gol_hex() {
+	use render::{MemoryRenderer};
+	use params::SIZE_GRID_PIXELS;
+
+	//With every cell alive, edge cells miss the neighbors off the map. Odd rows reach right, even rows left.
+	let six = Neighborhood::hexagonal();
+	let full = vec![vec![ALIVE; 5]; 4];
+	assert_eq!(2, six.count(&full, 0, 0));
+	assert_eq!(3, six.count(&full, 4, 0));
+	assert_eq!(5, six.count(&full, 0, 1));
+	assert_eq!(3, six.count(&full, 4, 1));
+	assert_eq!(6, six.count(&full, 2, 1));
+	assert_eq!(6, six.count(&full, 2, 2));
+	assert_eq!(2, six.count(&full, 4, 3));
+
+	//The rows above and below an odd row count the cells straight over and one right
+	let mut locals = vec![vec![DEAD; 5]; 4];
+	for &(x, y, odd, even) in [(1, 0, 0, 0), (2, 0, 1, 0), (3, 0, 1, 0), (1, 1, 1, 1), (3, 1, 1, 0), (1, 2, 0, 1), (3, 2, 1, 1), (1, 3, 0, 1), (3, 3, 0, 0)].iter() {
+		locals[y][x] = ALIVE;
+		assert_eq!(odd, six.count(&locals, 2, 1), "odd row with {}, {}", x, y);
+		assert_eq!(even, six.count(&locals, 2, 2), "even row with {}, {}", x, y);
+		locals[y][x] = DEAD;
+	}
+
+	//Under B2/S34H two lone neighbors die and give birth to the two cells they share
+	let mut world = World::generate(0, (5, 4), None);
+	world.set_rule(Rule::parse("B2/S34H").unwrap());
+	world.set_cell(1, 1, true);
+	world.set_cell(2, 1, true);
+	let stats = world.update();
+	assert_eq!((2, 2, 2), (stats.living, stats.births, stats.deaths));
+	assert_eq!((Some(true), Some(true)), (world.get_cell(2, 0), world.get_cell(2, 2)));
+
+	//Clicks land on the hexagon drawn, which on odd rows is shifted half a cell right
+	let camera = Camera::new((0.0, 0.0, 100.0, 100.0));
+	assert_eq!(Some((0, 1)), world.grid_at(&camera, SIZE_GRID_PIXELS, SIZE_GRID_PIXELS * 3 / 2));
+	assert_eq!(Some((-1, 1)), world.cell_at(&camera, 0, SIZE_GRID_PIXELS));
+	assert_eq!(None, world.grid_at(&camera, 0, SIZE_GRID_PIXELS));
+	assert_eq!(Some((0, 0)), world.grid_at(&camera, 0, SIZE_GRID_PIXELS - 2));
+	world.set_rule(Rule::classic());
+	assert_eq!(Some((0, 1)), world.grid_at(&camera, 0, SIZE_GRID_PIXELS));
+	world.set_rule(Rule::parse("B2/S34H").unwrap());
+
+	//Each living cell is drawn as a hexagon around its middle, less the boarder
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+	let polygons = renderer.polygons();
+	assert_eq!(2, polygons.len());
+	let zoom = SIZE_GRID_PIXELS as f32;
+	let top = (2.5 * zoom, 0.5 * zoom - 0.625 * (zoom - 1.0));
+	assert_eq!((COLOR_CELL, 6), (polygons[0].0, polygons[0].1.len()));
+	assert!((polygons[0].1[0].0 - top.0).abs() < 0.001 && (polygons[0].1[0].1 - top.1).abs() < 0.001);
+	//The world behind takes in the half cell odd rows stick out
+	let (_, (_, _, width, height)) = renderer.rects()[0];
+	assert_eq!((5.5 * zoom, 4.0 * zoom), (width, height));
+}
+
+#[test]
+fn test_gol_isotropic() {
+	//tlife keeps the glider, but the blinker's middle has two opposite neighbors and dies
+	let mut world = World::generate(0, (12, 12), None);
+	world.set_rule(Rule::parse("B3/S2-i34q").unwrap());
+	for &(x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].iter() {
+		world.set_cell(x, y, true);
+	}
+	for _ in 0..8 {
+		world.update();
+	}
+	assert_eq!(Some((2, 2, 3, 3)), world.stats().bounds);
+	assert_eq!(5, world.stats().living);
+
+	world.fill((0, 0, 12, 12), false);
+	world.set_line((5, 4), (5, 6), true);
+	assert_eq!(2, world.update().living);
+	assert_eq!(Some((4, 5, 3, 1)), world.stats().bounds);
+	assert_eq!(0, world.update().living);
+
+	//Snowflakes grows a row of three into a still life shaped like a snowflake, keeping its symmetry
+	let mut world = World::generate(0, (21, 21), None);
+	world.set_rule(Rule::parse("B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e").unwrap());
+	world.set_line((9, 10), (11, 10), true);
+	let sizes: Vec<u64> = (0..4).map(|_| world.update().living).collect();
+	assert_eq!(vec![2, 5, 8, 13], sizes);
+	for _ in 0..2 {
+		world.update();
+	}
+	let still = world.fingerprint();
+	assert_eq!(0, world.update().changed);
+	assert_eq!(still, world.fingerprint());
+	assert_eq!((21, Some((7, 7, 7, 7))), (world.stats().living, world.stats().bounds));
+	for y in 0..21 {
+		for x in 0..21 {
+			assert_eq!(world.get_cell(x, y), world.get_cell(20 - x, y));
+			assert_eq!(world.get_cell(x, y), world.get_cell(y, x));
+		}
+	}
+}
+
+#[test]
+fn test_gol_wireworld() {
+	let circuit = |rle: &str, size: (i32, i32)| {
+		let mut world = World::generate(0, size, None);
+		world.set_rule(Rule::wireworld());
+		world.stamp(&Pattern::parse_rle("circuit", rle).unwrap(), (0, 0));
+		world
+	};
+
+	//A diode lets an electron through from the left, splitting and joining again around the gap
+	let mut diode = circuit("3.2C$BA2C.4C$3.2C!", (9, 3));
+	let heads: Vec<u64> = (0..8).map(|_| diode.update().living).collect();
+	assert_eq!(vec![1, 3, 2, 1, 1, 1, 1, 0], heads);
+	assert_eq!(Some(TAIL), diode.get_state(8, 1));
+
+	//From the right, the three heads meeting the wire on the left are too many to spark it
+	let mut diode = circuit("3.2C$4C.2CAB$3.2C!", (9, 3));
+	let heads: Vec<u64> = (0..8).map(|_| diode.update().living).collect();
+	assert_eq!(vec![1, 1, 2, 3, 0, 0, 0, 0], heads);
+	assert_eq!(Some(CONDUCTOR), diode.get_state(2, 1));
+
+	//A clock sends its electron around a loop of ten cells every ten generations
+	let mut clock = circuit(".BA2C$C4.C$.4C!", (6, 3));
+	let start = clock.fingerprint();
+	for generation in 1..11 {
+		assert_eq!(1, clock.update().living);
+		assert_eq!(generation == 10, clock.fingerprint() == start);
+	}
+
+	//The pen lays wire and the electron brush moves conductors through the states of an electron
+	let mut world = World::generate(0, (5, 5), None);
+	world.set_rule(Rule::wireworld());
+	world.set_line_state((0, 2), (4, 2), world.rule().painted());
+	world.toggle_cell(0, 0);
+	world.toggle_cell(4, 2);
+	assert_eq!((Some(CONDUCTOR), Some(DEAD)), (world.get_state(0, 0), world.get_state(4, 2)));
+	let sparks: Vec<Option<State>> = (0..3).map(|_| { world.spark(1, 2); world.get_state(1, 2) }).collect();
+	assert_eq!(vec![Some(HEAD), Some(TAIL), Some(CONDUCTOR)], sparks);
+	world.spark(1, 1);
+	assert_eq!(Some(DEAD), world.get_state(1, 1));
+
+	//Copies keep the states and the rule, so they save as WireWorld patterns
+	world.spark(2, 2);
+	let copy = world.copy((0, 2, 5, 1)).unwrap();
+	assert_eq!((&vec![CONDUCTOR, CONDUCTOR, HEAD, CONDUCTOR], Some("WireWorld")), (copy.states(), copy.rule()));
+	world.stamp(&copy, (0, 4));
+	assert_eq!(Some(HEAD), world.get_state(2, 4));
+
+	//Other rules don't spark cells
+	let mut life = World::generate(0, (3, 3), None);
+	life.set_state(1, 1, CONDUCTOR);
+	life.spark(1, 1);
+	assert_eq!(Some(CONDUCTOR), life.get_state(1, 1));
+}
+
+#[test]
+fn test_gol_colors() {
+	use render::{MemoryRenderer};
+
+	//A blinker of two red cells and a blue one turns red, since two of each newborn's three parents are red
+	let mut world = World::generate(0, (6, 6), None);
+	world.set_rule(Rule::parse("Immigration").unwrap());
+	world.set_line((1, 2), (2, 2), true);
+	assert_eq!(Some(1), world.next_paint());
+	world.set_cell(3, 2, true);
+	assert_eq!((Some(0), Some(1), None), (world.color(1, 2), world.color(3, 2), world.color(0, 0)));
+	assert_eq!([2, 1, 0, 0], world.stats().colors);
+	assert_eq!([3, 0, 0, 0], world.update().colors);
+	assert_eq!((Some(0), Some(0), Some(0)), (world.color(2, 1), world.color(2, 2), world.color(2, 3)));
+
+	//Under QuadLife, the cell born to three parents of three colors takes the fourth
+	let mut world = World::generate(0, (4, 4), None);
+	world.set_rule(Rule::parse("QuadLife").unwrap());
+	for &(x, y) in [(1, 1), (2, 1), (1, 2)].iter() {
+		world.set_cell(x, y, true);
+		world.next_paint();
+	}
+	assert_eq!([1, 1, 1, 1], world.update().colors);
+	assert_eq!(Some(3), world.color(2, 2));
+	assert_eq!([0, 1, 1, 1], { world.set_cell(1, 1, false); world.stats().colors });
+
+	//Each color is drawn in its own player's color
+	let camera = Camera::new((0.0, 0.0, 40.0, 40.0));
+	let mut renderer = MemoryRenderer::new();
+	world.draw(&mut renderer, &camera);
+	let colors: Vec<Color> = renderer.rects().into_iter().skip(1).map(|(color, _)| color).collect();
+	assert_eq!(vec![PLAYER_COLORS[1], PLAYER_COLORS[2], PLAYER_COLORS[3]], colors);
+
+	//Colors are dealt out to the living cells by a rule bringing them, and dropped by one without
+	let mut world = World::generate(50, (10, 10), Some(3));
+	world.set_rule(Rule::colored(2));
+	let colors = world.stats().colors;
+	assert_eq!(50, colors[0] + colors[1]);
+	assert!(colors[0] > 0 && colors[1] > 0);
+	world.set_rule(Rule::classic());
+	assert_eq!(([0; MAX_COLORS], None), (world.stats().colors, world.next_paint()));
+}
+
+#[test]
+fn test_gol_elementary() {
+	use elementary::{Boundary};
+
+	let rows = |world: &World| -> Vec<String> {
+		(0..world.size().1).map(|y| (0..world.size().0).map(|x| if world.get_cell(x, y) == Some(true) { 'o' } else { '.' }).collect()).collect()
+	};
+
+	//Rule 30 grows a row a generation down from one cell in the middle
+	let mut world = World::generate(10, (7, 4), Some(1));
+	world.set_rule(Rule::parse("W30").unwrap());
+	world.start_line(Start::Single, None);
+	assert_eq!(1, world.stats().living);
+	for _ in 0..3 {
+		world.update();
+	}
+	assert_eq!(vec!["...o...", "..ooo..", ".oo..o.", "oo.oooo"], rows(&world));
+	assert_eq!((6, 0), (world.stats().births, world.stats().deaths));
+
+	//Past the bottom the diagram scrolls up, and the row off the top died
+	let stats = world.update();
+	assert_eq!(vec!["..ooo..", ".oo..o.", "oo.oooo", "o..o..."], rows(&world));
+	assert_eq!((2, 1, 14), (stats.births, stats.deaths, stats.living));
+	assert_eq!(Some((0, 0, 7, 4)), stats.bounds);
+
+	//A wrapped boundary reaches around to the other end
+	world.set_rule(world.rule().with_boundary(Boundary::Wrap).unwrap());
+	world.update();
+	assert_eq!("ooooo.o", rows(&world)[3]);
+
+	//A random first row only fills the top row, the same way for the same seed
+	let mut first = World::generate(0, (20, 5), None);
+	first.start_line(Start::Random, Some(4));
+	let mut again = World::generate(30, (20, 5), None);
+	again.start_line(Start::Random, Some(4));
+	assert_eq!(first.fingerprint(), again.fingerprint());
+	assert_eq!(Some(0), first.bounds().map(|(_, y, _, height)| y + height - 1));
+	assert_eq!(first.stats().living, again.stats().living);
+}
+
+#[test]
+fn test_gol_image() {
+	//Each cell is a square of pixels, living cells in their own color over the background
+	let mut world = World::generate(0, (3, 2), None);
+	world.set_cell(1, 0, true);
+	let (width, height, rgba) = world.image(2);
+	assert_eq!((6, 4, 6 * 4 * 4), (width, height, rgba.len()));
+	let pixel = |x: usize, y: usize| -> &[u8] { &rgba[(y * 6 + x) * 4..(y * 6 + x) * 4 + 4] };
+	assert_eq!(&[245, 245, 245, 255], pixel(0, 0));
+	assert_eq!(&[245, 245, 245, 255], pixel(3, 2));
+	assert!(pixel(2, 0) != pixel(0, 0));
+	assert_eq!(255, pixel(2, 0)[3]);
+	assert_eq!(pixel(2, 0), pixel(3, 1));
+
+	//A one dimensional diagram is pictured the same way, a row of cells to each generation
+	let mut world = World::generate(0, (5, 3), None);
+	world.set_rule(Rule::parse("W30").unwrap());
+	world.start_line(Start::Single, None);
+	world.update();
+	let (_, _, rgba) = world.image(1);
+	let alive: Vec<bool> = rgba.chunks(4).map(|pixel| pixel[0] != 245).collect();
+	assert_eq!(vec![false, false, true, false, false, false, true, true, true, false], alive[..10].to_vec());
+}
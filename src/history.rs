@@ -0,0 +1,265 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Edit history. Each edit the user makes is kept as an Edit that knows how to
+// reverse itself on a world, and reversing an edit gives back the edit that
+// reverses it again. Undo and redo just move edits between two stacks that way.
+
+use std::mem;
+
+use gol::{World};
+use rule::{Rule, State};
+
+///Most edits kept for undoing. Restarts keep a whole world, so the history can't grow forever.
+pub const MAX_HISTORY: usize = 64;
+///Most replaced worlds kept for undoing, since a large world can take many megabytes
+pub const MAX_WORLDS: usize = 8;
+
+///A change the user made to the world
+pub enum Edit {
+	///Cells edited together, such as by one brush stroke, with their old and new states in order
+	Cells(Vec<(i32, i32, State, State)>),
+	///A world that was replaced, such as by a restart
+	World(Box<World>),
+	///A rule that was replaced
+	Rule(Rule),
+}
+
+impl Edit {
+	/// Returns true if the edit holds a whole world
+	fn is_world(&self) -> bool {
+		matches!(*self, Edit::World(_))
+	}
+
+	/// Reverses the edit on a world.
+	///
+	/// # Arguments
+	/// * 'world' - The world the edit was made to.
+	///
+	/// # Return
+	/// * Edit - The edit that reverses it back.
+	fn revert(self, world: &mut World) -> Edit {
+		match self {
+			Edit::Cells(cells) => {
+				//Last changed, first restored, in case a cell changed more than once
+				for &(x, y, old, _) in cells.iter().rev() {
+					world.set_state(x, y, old);
+				}
+				Edit::Cells(cells.into_iter().rev().map(|(x, y, old, new)| (x, y, new, old)).collect())
+			},
+			Edit::World(mut other) => {
+				//Recording carries on with whichever world is current
+				other.set_recorder(world.take_recorder());
+				Edit::World(Box::new(mem::replace(world, *other)))
+			},
+			Edit::Rule(rule) => {
+				let other = world.rule().clone();
+				world.set_rule(rule);
+				Edit::Rule(other)
+			},
+		}
+	}
+}
+
+///Edits that can be undone, and undone edits that can be redone
+pub struct History {
+	undo: Vec<Edit>,
+	redo: Vec<Edit>,
+}
+
+impl History {
+	/// Generates an empty history
+	pub fn new() -> Self {
+		History { undo: Vec::new(), redo: Vec::new() }
+	}
+
+	/// Adds an edit that has just been made. Edits undone before it can no longer be redone.
+	///
+	/// # Arguments
+	/// * 'edit' - The edit, holding what is needed to reverse it.
+	pub fn push(&mut self, edit: Edit) {
+		//Clicks and strokes that changed nothing aren't worth undoing
+		if let Edit::Cells(ref cells) = edit {
+			if cells.is_empty() {
+				return;
+			}
+		}
+		self.redo.clear();
+		self.undo.push(edit);
+		if self.undo.len() > MAX_HISTORY {
+			self.undo.remove(0);
+		}
+		//Edits older than a dropped world were made to that world, so they go with it
+		while self.undo.iter().filter(|edit| edit.is_world()).count() > MAX_WORLDS {
+			let oldest = self.undo.iter().position(|edit| edit.is_world()).unwrap();
+			self.undo.drain(..oldest + 1);
+		}
+	}
+
+	/// Reverses the last edit.
+	///
+	/// # Arguments
+	/// * 'world' - The world being edited.
+	///
+	/// # Return
+	/// * bool - True if there was an edit to undo.
+	pub fn undo(&mut self, world: &mut World) -> bool {
+		match self.undo.pop() {
+			Some(edit) => {
+				self.redo.push(edit.revert(world));
+				true
+			},
+			None => false,
+		}
+	}
+
+	/// Makes the last undone edit again.
+	///
+	/// # Arguments
+	/// * 'world' - The world being edited.
+	///
+	/// # Return
+	/// * bool - True if there was an edit to redo.
+	pub fn redo(&mut self, world: &mut World) -> bool {
+		match self.redo.pop() {
+			Some(edit) => {
+				self.undo.push(edit.revert(world));
+				true
+			},
+			None => false,
+		}
+	}
+}
+
+#[test]
+fn test_history_cells() {
+	let mut world = World::generate(0, (10, 10), None);
+	let mut history = History::new();
+
+	//A stroke is undone as one edit, and only cells it changed are restored
+	world.set_cell(2, 0, true);
+	world.record();
+	world.set_line((0, 0), (4, 0), true);
+	history.push(Edit::Cells(world.take_changes()));
+	world.record();
+	world.toggle_cell(0, 5);
+	world.toggle_cell(0, 5);
+	world.toggle_cell(1, 5);
+	history.push(Edit::Cells(world.take_changes()));
+	assert_eq!(6, world.stats().living);
+
+	assert!(history.undo(&mut world));
+	assert_eq!(5, world.stats().living);
+	assert_eq!(Some(false), world.get_cell(0, 5));
+	assert!(history.undo(&mut world));
+	assert_eq!(1, world.stats().living);
+	assert_eq!(Some(true), world.get_cell(2, 0));
+	assert!(!history.undo(&mut world));
+
+	assert!(history.redo(&mut world));
+	assert!(history.redo(&mut world));
+	assert!(!history.redo(&mut world));
+	assert_eq!(6, world.stats().living);
+	assert_eq!(Some(false), world.get_cell(0, 5));
+	assert_eq!(Some(true), world.get_cell(1, 5));
+
+	//Edits that changed nothing are dropped, and a new edit ends the redos
+	history.push(Edit::Cells(Vec::new()));
+	assert!(history.undo(&mut world));
+	world.record();
+	world.set_cell(9, 9, true);
+	history.push(Edit::Cells(world.take_changes()));
+	assert!(!history.redo(&mut world));
+	assert!(history.undo(&mut world));
+	assert!(history.undo(&mut world));
+	assert_eq!(1, world.stats().living);
+}
+
+#[test]
+fn test_history_worlds_and_rules() {
+	let mut world = World::generate(20, (10, 10), Some(1));
+	let mut history = History::new();
+	let first = world.fingerprint();
+
+	//A restart brings back the whole old world, living count and all
+	let old = mem::replace(&mut world, World::generate(40, (20, 20), Some(2)));
+	history.push(Edit::World(Box::new(old)));
+	let old = world.rule().clone();
+	world.set_rule(Rule::parse("B36/S23").unwrap());
+	history.push(Edit::Rule(old));
+
+	assert!(history.undo(&mut world));
+	assert_eq!(&Rule::classic(), world.rule());
+	assert!(history.undo(&mut world));
+	assert_eq!(first, world.fingerprint());
+	assert_eq!(20, world.stats().living);
+	assert!(history.redo(&mut world));
+	assert_eq!((20, 20), world.size());
+	assert!(history.redo(&mut world));
+	assert_eq!("B36/S23", world.rule().to_string());
+
+	//Only the latest edits are kept
+	for _ in 0..MAX_HISTORY + 5 {
+		world.record();
+		world.toggle_cell(0, 0);
+		history.push(Edit::Cells(world.take_changes()));
+	}
+	let mut undone = 0;
+	while history.undo(&mut world) {
+		undone += 1;
+	}
+	assert_eq!(MAX_HISTORY, undone);
+}
+
+#[test]
+fn test_history_line_rule() {
+	let mut world = World::generate(20, (10, 10), Some(1));
+	let mut history = History::new();
+	let first = world.fingerprint();
+
+	//Switching to a one dimensional rule replaces the world, and one undo brings back the rule and map
+	let mut line = World::generate(0, (10, 10), Some(1));
+	line.set_rule(Rule::parse("W30").unwrap());
+	line.start_line(::elementary::Start::Single, Some(1));
+	let old = mem::replace(&mut world, line);
+	history.push(Edit::World(Box::new(old)));
+
+	assert!(history.undo(&mut world));
+	assert_eq!(&Rule::classic(), world.rule());
+	assert_eq!(first, world.fingerprint());
+	assert!(!history.undo(&mut world));
+	assert!(history.redo(&mut world));
+	assert_eq!("W30", world.rule().to_string());
+}
+
+#[test]
+fn test_history_world_limit() {
+	let mut world = World::generate(0, (10, 10), Some(1));
+	let mut history = History::new();
+
+	//Only the latest worlds are kept, along with the edits made since the oldest of them
+	for size in 0..MAX_WORLDS as i32 + 2 {
+		world.record();
+		world.toggle_cell(0, 0);
+		history.push(Edit::Cells(world.take_changes()));
+		let old = mem::replace(&mut world, World::generate(0, (10 + size, 10), Some(1)));
+		history.push(Edit::World(Box::new(old)));
+	}
+	let mut undone = 0;
+	while history.undo(&mut world) {
+		undone += 1;
+	}
+	assert_eq!(2 * MAX_WORLDS, undone);
+	assert_eq!((11, 10), world.size());
+}
@@ -0,0 +1,366 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Keyboard input. Keys are bound to Actions through a Keymap, which can be
+// loaded from a config file with one binding per line:
+//
+//     # comment
+//     Space  = pause
+//     N      = step
+//     Ctrl+Z = undo
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+//Import graphics crate
+extern crate ggez;
+use ggez::event::{Keycode};
+
+///Tools used to edit cells with the mouse
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Brush {
+	///Left click toggles a cell, left drag paints life, right drag erases
+	Pen,
+	///Left and right drags both erase
+	Eraser,
+	///Left drag pans the camera
+	Hand,
+	///Left click places the picked pattern
+	Stamp,
+	///Left drag selects a block of cells
+	Select,
+	///Left click moves a WireWorld conductor to an electron head, a head to a tail, and a tail back
+	Electron,
+}
+
+///Everything the user can ask the game to do
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+	Pause,
+	Step,
+	StepMany,
+	RunUntilStable,
+	Restart,
+	Clear,
+	SpeedUp,
+	SpeedDown,
+	PanLeft,
+	PanRight,
+	PanUp,
+	PanDown,
+	Fit,
+	Rotate,
+	FlipHorizontal,
+	FlipVertical,
+	Copy,
+	Cut,
+	Paste,
+	ClearSelection,
+	FillRandom,
+	SaveSelection,
+	ExportImage,
+	NextColor,
+	Undo,
+	Redo,
+	Brush(Brush),
+}
+
+impl Action {
+	/// Looks up an action by the name used in config files.
+	///
+	/// # Arguments
+	/// * 'name' - The name of the action, such as "pause" or "brush_pen".
+	///
+	/// # Return
+	/// * Option<Action> - The action, None if the name is unknown.
+	pub fn from_name(name: &str) -> Option<Action> {
+		match name.to_lowercase().as_str() {
+			"pause" => Some(Action::Pause),
+			"step" => Some(Action::Step),
+			"step_many" => Some(Action::StepMany),
+			"run_until_stable" => Some(Action::RunUntilStable),
+			"restart" => Some(Action::Restart),
+			"clear" => Some(Action::Clear),
+			"speed_up" => Some(Action::SpeedUp),
+			"speed_down" => Some(Action::SpeedDown),
+			"pan_left" => Some(Action::PanLeft),
+			"pan_right" => Some(Action::PanRight),
+			"pan_up" => Some(Action::PanUp),
+			"pan_down" => Some(Action::PanDown),
+			"fit" => Some(Action::Fit),
+			"rotate" => Some(Action::Rotate),
+			"flip_horizontal" => Some(Action::FlipHorizontal),
+			"flip_vertical" => Some(Action::FlipVertical),
+			"copy" => Some(Action::Copy),
+			"cut" => Some(Action::Cut),
+			"paste" => Some(Action::Paste),
+			"clear_selection" => Some(Action::ClearSelection),
+			"fill_random" => Some(Action::FillRandom),
+			"save_selection" => Some(Action::SaveSelection),
+			"export_image" => Some(Action::ExportImage),
+			"next_color" => Some(Action::NextColor),
+			"undo" => Some(Action::Undo),
+			"redo" => Some(Action::Redo),
+			"brush_pen" => Some(Action::Brush(Brush::Pen)),
+			"brush_eraser" => Some(Action::Brush(Brush::Eraser)),
+			"brush_hand" => Some(Action::Brush(Brush::Hand)),
+			"brush_stamp" => Some(Action::Brush(Brush::Stamp)),
+			"brush_select" => Some(Action::Brush(Brush::Select)),
+			"brush_electron" => Some(Action::Brush(Brush::Electron)),
+			_ => None,
+		}
+	}
+}
+
+/// Looks up a key by name. Names are case insensitive and match the labels on the keys,
+/// such as "A", "7", "Space", "Left" or "KpPlus" for keypad keys.
+///
+/// # Arguments
+/// * 'name' - The name of the key.
+///
+/// # Return
+/// * Option<Keycode> - The key, None if the name is unknown.
+pub fn key_from_name(name: &str) -> Option<Keycode> {
+	let name = name.to_lowercase();
+
+	//Letters and digits
+	if name.len() == 1 {
+		let keys = [
+			Keycode::A, Keycode::B, Keycode::C, Keycode::D, Keycode::E, Keycode::F, Keycode::G,
+			Keycode::H, Keycode::I, Keycode::J, Keycode::K, Keycode::L, Keycode::M, Keycode::N,
+			Keycode::O, Keycode::P, Keycode::Q, Keycode::R, Keycode::S, Keycode::T, Keycode::U,
+			Keycode::V, Keycode::W, Keycode::X, Keycode::Y, Keycode::Z,
+		];
+		let digits = [
+			Keycode::Num0, Keycode::Num1, Keycode::Num2, Keycode::Num3, Keycode::Num4,
+			Keycode::Num5, Keycode::Num6, Keycode::Num7, Keycode::Num8, Keycode::Num9,
+		];
+		let c = name.chars().next().unwrap();
+		if c.is_ascii_lowercase() {
+			return Some(keys[(c as u8 - b'a') as usize]);
+		}
+		if c.is_ascii_digit() {
+			return Some(digits[(c as u8 - b'0') as usize]);
+		}
+	}
+
+	match name.as_str() {
+		"space" => Some(Keycode::Space),
+		"return" | "enter" => Some(Keycode::Return),
+		"backspace" => Some(Keycode::Backspace),
+		"tab" => Some(Keycode::Tab),
+		"escape" => Some(Keycode::Escape),
+		"delete" => Some(Keycode::Delete),
+		"left" => Some(Keycode::Left),
+		"right" => Some(Keycode::Right),
+		"up" => Some(Keycode::Up),
+		"down" => Some(Keycode::Down),
+		"plus" | "+" => Some(Keycode::Plus),
+		"minus" | "-" => Some(Keycode::Minus),
+		"equals" | "=" => Some(Keycode::Equals),
+		"kpplus" => Some(Keycode::KpPlus),
+		"kpminus" => Some(Keycode::KpMinus),
+		_ => None,
+	}
+}
+
+/// Looks up a key by name, along with whether Ctrl is held. Ctrl is written in front of
+/// the key, such as "Ctrl+Z".
+///
+/// # Arguments
+/// * 'name' - The name of the key.
+///
+/// # Return
+/// * Option<(Keycode, bool)> - The key and true if Ctrl is held, None if the name is unknown.
+pub fn chord_from_name(name: &str) -> Option<(Keycode, bool)> {
+	let lower = name.to_lowercase();
+	//Leave a lone '+' as the plus key
+	if lower.starts_with("ctrl+") && lower.len() > "ctrl+".len() {
+		return key_from_name(&lower["ctrl+".len()..]).map(|key| (key, true));
+	}
+	key_from_name(name).map(|key| (key, false))
+}
+
+///Binds keys to actions
+pub struct Keymap {
+	///Actions by key, and whether Ctrl is held
+	bindings: HashMap<(Keycode, bool), Action>,
+}
+
+impl Keymap {
+	/// Generates the default key bindings:
+	/// * Space pauses, N steps, M steps many generations, U runs until stable
+	/// * R restarts and C clears the map
+	/// * +/- change the speed
+	/// * Arrows pan and F fits the living cells into view
+	/// * T rotates the selection or picked pattern, H and V flip it
+	/// * Y copies the selection, X cuts it and P pastes at the mouse
+	/// * Delete clears the selection, G fills it randomly and S saves it to the pattern library
+	/// * E saves a picture of the world
+	/// * Ctrl+Z undoes an edit and Ctrl+Y redoes it. Ctrl+C, Ctrl+X and Ctrl+V copy, cut and paste.
+	/// * Digits pick a brush
+	pub fn new() -> Self {
+		let mut bindings = HashMap::new();
+		bindings.insert((Keycode::Space, false), Action::Pause);
+		bindings.insert((Keycode::N, false), Action::Step);
+		bindings.insert((Keycode::M, false), Action::StepMany);
+		bindings.insert((Keycode::U, false), Action::RunUntilStable);
+		bindings.insert((Keycode::R, false), Action::Restart);
+		bindings.insert((Keycode::C, false), Action::Clear);
+		//'+' shares a key with '=' on most keyboards
+		bindings.insert((Keycode::Plus, false), Action::SpeedUp);
+		bindings.insert((Keycode::Equals, false), Action::SpeedUp);
+		bindings.insert((Keycode::KpPlus, false), Action::SpeedUp);
+		bindings.insert((Keycode::Minus, false), Action::SpeedDown);
+		bindings.insert((Keycode::KpMinus, false), Action::SpeedDown);
+		bindings.insert((Keycode::Left, false), Action::PanLeft);
+		bindings.insert((Keycode::Right, false), Action::PanRight);
+		bindings.insert((Keycode::Up, false), Action::PanUp);
+		bindings.insert((Keycode::Down, false), Action::PanDown);
+		bindings.insert((Keycode::F, false), Action::Fit);
+		bindings.insert((Keycode::T, false), Action::Rotate);
+		bindings.insert((Keycode::H, false), Action::FlipHorizontal);
+		bindings.insert((Keycode::V, false), Action::FlipVertical);
+		bindings.insert((Keycode::Y, false), Action::Copy);
+		bindings.insert((Keycode::X, false), Action::Cut);
+		bindings.insert((Keycode::P, false), Action::Paste);
+		bindings.insert((Keycode::Delete, false), Action::ClearSelection);
+		bindings.insert((Keycode::G, false), Action::FillRandom);
+		bindings.insert((Keycode::S, false), Action::SaveSelection);
+		bindings.insert((Keycode::E, false), Action::ExportImage);
+		bindings.insert((Keycode::K, false), Action::NextColor);
+		bindings.insert((Keycode::Num1, false), Action::Brush(Brush::Pen));
+		bindings.insert((Keycode::Num2, false), Action::Brush(Brush::Eraser));
+		bindings.insert((Keycode::Num3, false), Action::Brush(Brush::Hand));
+		bindings.insert((Keycode::Num4, false), Action::Brush(Brush::Stamp));
+		bindings.insert((Keycode::Num5, false), Action::Brush(Brush::Select));
+		bindings.insert((Keycode::Num6, false), Action::Brush(Brush::Electron));
+		bindings.insert((Keycode::Z, true), Action::Undo);
+		bindings.insert((Keycode::Y, true), Action::Redo);
+		bindings.insert((Keycode::C, true), Action::Copy);
+		bindings.insert((Keycode::X, true), Action::Cut);
+		bindings.insert((Keycode::V, true), Action::Paste);
+		Keymap { bindings }
+	}
+
+	/// Generates the default key bindings, then replaces them with any found in a config file.
+	///
+	/// # Arguments
+	/// * 'path' - The config file to read.
+	///
+	/// # Return
+	/// * Result<Keymap, String> - The key bindings, or a description of what is wrong with the file.
+	pub fn load(path: &Path) -> Result<Keymap, String> {
+		let mut text = String::new();
+		File::open(path)
+			.and_then(|mut file| file.read_to_string(&mut text))
+			.map_err(|error| format!("{}: {}", path.display(), error))?;
+
+		let mut keymap = Keymap::new();
+		keymap.parse(&text)?;
+		Ok(keymap)
+	}
+
+	/// Adds the bindings in a config string, replacing existing bindings for the same keys.
+	/// Binding a key to "none" removes its binding.
+	///
+	/// # Arguments
+	/// * 'text' - Lines of "key = action". Blank lines and lines starting with '#' are ignored.
+	///
+	/// # Return
+	/// * Result<(), String> - A description of the first bad line, if any.
+	pub fn parse(&mut self, text: &str) -> Result<(), String> {
+		for (i, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			//Split on the last '=' so the '=' key can be bound
+			let split = match line.rfind('=') {
+				Some(split) if split > 0 => split,
+				_ => return Err(format!("line {}: expected 'key = action'", i + 1)),
+			};
+			let (key, action) = (line[..split].trim(), line[split + 1..].trim());
+
+			let key = chord_from_name(key).ok_or(format!("line {}: unknown key '{}'", i + 1, key))?;
+			if action.eq_ignore_ascii_case("none") {
+				self.bindings.remove(&key);
+				continue;
+			}
+			let action = Action::from_name(action).ok_or(format!("line {}: unknown action '{}'", i + 1, action))?;
+			self.bindings.insert(key, action);
+		}
+		Ok(())
+	}
+
+	/// Looks up the action bound to a key.
+	///
+	/// # Arguments
+	/// * 'key'  - The key that was pressed.
+	/// * 'ctrl' - True if Ctrl was held. Keys bound without Ctrl don't fire while it is held.
+	pub fn action(&self, key: Keycode, ctrl: bool) -> Option<Action> {
+		self.bindings.get(&(key, ctrl)).cloned()
+	}
+}
+
+#[test]
+fn test_keymap_defaults() {
+	let keymap = Keymap::new();
+	assert_eq!(Some(Action::Pause), keymap.action(Keycode::Space, false));
+	assert_eq!(Some(Action::Step), keymap.action(Keycode::N, false));
+	assert_eq!(Some(Action::RunUntilStable), keymap.action(Keycode::U, false));
+	assert_eq!(Some(Action::SpeedDown), keymap.action(Keycode::Minus, false));
+	assert_eq!(Some(Action::Brush(Brush::Eraser)), keymap.action(Keycode::Num2, false));
+	assert_eq!(Some(Action::Paste), keymap.action(Keycode::P, false));
+	assert_eq!(Some(Action::ExportImage), keymap.action(Keycode::E, false));
+	assert_eq!(None, keymap.action(Keycode::Q, false));
+	assert_eq!(Some(Action::Undo), keymap.action(Keycode::Z, true));
+	assert_eq!(None, keymap.action(Keycode::Z, false));
+	assert_eq!(Some(Action::Restart), keymap.action(Keycode::R, false));
+	assert_eq!(None, keymap.action(Keycode::R, true));
+}
+
+#[test]
+fn test_keymap_parse() {
+	let mut keymap = Keymap::new();
+	keymap.parse("
+		# Rebind pause and use WASD to pan
+		P     = pause
+		a     = PAN_LEFT
+		=     = speed_up
+		Space = step
+		ctrl+U = redo
+		Ctrl++ = speed_down
+		R      = none
+		Ctrl+Z = NONE
+	").expect("Valid config");
+	assert_eq!(Some(Action::Pause), keymap.action(Keycode::P, false));
+	assert_eq!(Some(Action::PanLeft), keymap.action(Keycode::A, false));
+	assert_eq!(Some(Action::SpeedUp), keymap.action(Keycode::Equals, false));
+	assert_eq!(Some(Action::Step), keymap.action(Keycode::Space, false));
+	//Keys bound to none do nothing, and untouched bindings keep their defaults
+	assert_eq!(None, keymap.action(Keycode::R, false));
+	assert_eq!(None, keymap.action(Keycode::Z, true));
+	assert_eq!(Some(Action::Clear), keymap.action(Keycode::C, false));
+	assert_eq!(Some(Action::Redo), keymap.action(Keycode::U, true));
+	assert_eq!(Some(Action::SpeedDown), keymap.action(Keycode::Plus, true));
+	assert_eq!(Some(Action::RunUntilStable), keymap.action(Keycode::U, false));
+
+	assert_eq!(Err("line 2: unknown key 'Hyper'".to_string()), keymap.parse("\nHyper = pause"));
+	assert_eq!(Err("line 1: unknown action 'fly'".to_string()), keymap.parse("F = fly"));
+	assert_eq!(Err("line 1: expected 'key = action'".to_string()), keymap.parse("pause"));
+}
@@ -14,37 +14,215 @@
 
 //Grab env and path for use with external files
 use std::env;
+use std::mem;
 use std::path;
+use std::time::{Duration, Instant};
 
 //Import graphics crate
 extern crate ggez;
-use ggez::event::{self, MouseButton};
-use ggez::{Context, GameResult, graphics};
+use ggez::event::{self, Keycode, Mod, MouseButton, MouseState, LCTRLMOD, RCTRLMOD};
+use ggez::{Context, GameResult, graphics, mouse, timer};
 
 //Import global parameters
 mod params;
 use params::{
-	WIDTH_UI_INDENT, HEIGHT_UI_LINE, AREA_WINDOW_PIXELS,
+	WIDTH_UI_INDENT, AREA_WINDOW_PIXELS, SIZE_GRID_PIXELS, MAX_WORLD_GRIDS,
+	WIDTH_GAME_GRIDS, HEIGHT_GAME_GRIDS, POS_GAME_GRIDS,
 	WIDTH_STAT_GRIDS, HEIGHT_STAT_GRIDS, POS_STAT_GRIDS,
 	WIDTH_ADVANCED_GRIDS, HEIGHT_ADVANCED_GRIDS, POS_ADVANCED_GRIDS, 
-	WIDTH_OPTION_GRIDS, HEIGHT_OPTION_GRIDS, POS_OPTION_GRIDS
+	WIDTH_OPTION_GRIDS, HEIGHT_OPTION_GRIDS, POS_OPTION_GRIDS,
+	WIDTH_PATTERN_GRIDS, HEIGHT_PATTERN_GRIDS, POS_PATTERN_GRIDS,
+	WIDTH_MINIMAP_GRIDS, HEIGHT_MINIMAP_GRIDS, POS_MINIMAP_GRIDS, HEIGHT_UI_LINE
 };
 
 //Import game of life managment
 mod gol;
-use gol::{World};
+use gol::{World, Overlay, OVERLAYS, Settled, StabilityWatch};
+mod rule;
+use rule::{Rule, State, DEAD};
+mod elementary;
+use elementary::{Boundary, Start};
+mod neighborhood;
+use neighborhood::{Neighborhood};
+mod pattern;
+use pattern::{Pattern};
+mod history;
+use history::{History, Edit};
+mod recorder;
+use recorder::{Recorder, Format};
+mod export;
+use export::{save_png};
+
+//Import simulation timing
+mod timing;
+use timing::{Clock, Speed, SPEEDS};
+
+//Import the camera over the game grid
+mod camera;
+use camera::{Camera};
+
+//Import drawing backends
+mod render;
+use render::{GgezRenderer, Renderer, Color, Gradient};
+
+//Import keyboard controls
+mod input;
+use input::{Action, Brush, Keymap};
 
 //Import user interface managment
 mod ui;
-use ui::{UiElem, Frame};
+use ui::{UiElem, UiEvent, Widget, WidgetEvent, Label, Button, Toggle, Checkbox, Slider, TextInput, Minimap, List, Chart};
+
+///Identifies the widgets in the side panels
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Control {
+	//Stats
+	Generation,
+	Living,
+	Colors,
+	Peak,
+	Changes,
+	Totals,
+	Bounds,
+	Cell,
+	Timing,
+	Status,
+	Chart,
+	//Options
+	Pause,
+	Step,
+	StepMany,
+	StepCount,
+	RunUntilStable,
+	Restart,
+	Clear,
+	Fit,
+	Eraser,
+	ShowChanges,
+	ShowTrails,
+	ShowHeatmap,
+	Speed,
+	//Advanced options
+	Rule,
+	Neighborhood,
+	Population,
+	GridSize,
+	Seed,
+	SeedRow,
+	Boundary,
+	ChartLength,
+	Record,
+	Ages,
+	//Patterns
+	Patterns,
+	//Minimap
+	Minimap,
+}
+
+///Most generations a single Step N can run
+const MAX_STEP_COUNT: u32 = 10000;
+
+///Generations shown by the population chart unless changed in the advanced options
+const CHART_LENGTH: usize = 200;
+
+///Most generations the population chart can show
+const MAX_CHART_LENGTH: usize = 10000;
+
+///Fewest lines of ui text the population chart takes, legend included
+const MIN_CHART_LINES: i32 = 5;
+
+///Pixels on the longest side of exported pictures, unless the world has more cells than that
+const SIZE_EXPORT_PIXELS: i32 = 1000;
+
+///Colors of the living, births and deaths lines in the population chart
+const COLOR_LIVING: Color = [0.2, 0.4, 0.9, 1.0];
+const COLOR_BIRTHS: Color = [0.2, 0.7, 0.3, 1.0];
+const COLOR_DEATHS: Color = [0.85, 0.3, 0.2, 1.0];
+
+///Color of the picked pattern previewed under the mouse
+const COLOR_PREVIEW: Color = [0.2, 0.4, 0.9, 0.5];
+
+///Color laid over the selected cells
+const COLOR_SELECTION: Color = [0.2, 0.4, 0.9, 0.25];
+
+///Chance of each selected cell coming to life when the selection is filled randomly
+const FILL_DENSITY: f64 = 0.5;
+
+///Zoom for each notch of the mouse wheel
+const ZOOM_STEP: f32 = 1.25;
+
+///Pixels the camera moves for each press of a pan key
+const PAN_PIXELS: f32 = 50.0;
+
+///Time between redraws of the minimap, which is too slow to redraw every frame on big worlds
+const MINIMAP_INTERVAL: Duration = Duration::from_millis(250);
+
+///Most pixels on a side of the minimap picture
+const SIZE_MINIMAP_PIXELS: i32 = 256;
+
+///Longest time spent running generations in one frame, so drawing keeps up at any speed
+const MAX_UPDATE_TIME: Duration = Duration::from_millis(12);
+
+///Tracks a mouse button held down over the game grid
+struct Stroke {
+	///State painted into cells the mouse drags over
+	state: State,
+	///Last cell the mouse was over
+	last: (i32, i32),
+	///A stroke that never leaves its first cell is a click
+	dragged: bool,
+}
 
 ///Define game elements
 struct Game {
 	game: World,
-	option: UiElem<Frame>,
-	stat: UiElem<Frame>,
-	advanced: UiElem<Frame>,
+	option: UiElem<Control>,
+	stat: UiElem<Control>,
+	advanced: UiElem<Control>,
+	pattern: UiElem<Control>,
+	minimap: UiElem<Control>,
+	///When the minimap picture was last redrawn
+	minimap_drawn: Option<Instant>,
 	paused: bool,
+	///Initial population of new worlds
+	population: i32,
+	///(width, height) of new worlds
+	size: (i32, i32),
+	///Seed for new worlds, None for a different world every restart
+	seed: Option<u64>,
+	///First row of new worlds under one dimensional rules
+	start: Start,
+	///True while living cells are colored by age
+	ages: bool,
+	///Overlays drawn with the world
+	overlays: Vec<Overlay>,
+	clock: Clock,
+	///Generations run by Step N
+	step_count: u32,
+	///Watches the world while it runs until stable
+	watch: Option<StabilityWatch>,
+	brush: Brush,
+	stroke: Option<Stroke>,
+	camera: Camera,
+	///True while the mouse is dragging the camera
+	panning: bool,
+	///Cell under the mouse, None when it is outside of the game area
+	cursor: Option<(i32, i32)>,
+	///The pattern library
+	patterns: Vec<Pattern>,
+	///Directory the pattern library is loaded from and saved to
+	patterns_dir: Option<path::PathBuf>,
+	///Pattern placed by the stamp brush, as turned and flipped by the user
+	stamp: Option<Pattern>,
+	///Cells selected with the select brush, as (x, y, width, height) on the map
+	selection: Option<(i32, i32, i32, i32)>,
+	///Cell the selection was started from while the mouse is dragging it out
+	selecting: Option<(i32, i32)>,
+	///Cells last copied or cut, ready to paste
+	clipboard: Option<Pattern>,
+	///Edits that can be undone and redone
+	history: History,
+	keymap: Keymap,
 }
 
 //Implement game functions
@@ -53,44 +231,34 @@ impl Game {
 	/// Creates a game with classic Game of Life rules
 	pub fn classic() -> Self {
 		let x_offset = |x| { x * WIDTH_UI_INDENT };
-		let y_offset = |x| { x * HEIGHT_UI_LINE };
 
-		//UiElems define where the frames live in the window
+		//UiElems define where the panels live in the window and stack their widgets
+		let mut stats: Vec<Box<dyn Widget<Control>>> = vec![
+			Box::new(Label::new(Control::Generation, "Generation : ", "")),
+			Box::new(Label::new(Control::Living, "Living : ", "")),
+			Box::new(Label::new(Control::Colors, "Colors : ", "")),
+			Box::new(Label::new(Control::Peak, "Peak : ", "")),
+			Box::new(Label::new(Control::Changes, "Born / died : ", "")),
+			Box::new(Label::new(Control::Totals, "Total born / died : ", "")),
+			Box::new(Label::new(Control::Bounds, "Bounds : ", "")),
+			Box::new(Label::new(Control::Cell, "Cell : ", "")),
+			Box::new(Label::new(Control::Timing, "Speed : ", "")),
+			Box::new(Label::new(Control::Status, "Status : ", "")),
+		];
+		//The chart fills the rest of the panel below the header and labels
+		let chart_lines = HEIGHT_STAT_GRIDS / HEIGHT_UI_LINE - 1 - stats.len() as i32;
+		assert!(chart_lines >= MIN_CHART_LINES, "Error fitting the population chart in Stats");
+		stats.push(Box::new(Chart::new(Control::Chart, chart_lines, CHART_LENGTH, vec![
+			("Living", COLOR_LIVING),
+			("Births", COLOR_BIRTHS),
+			("Deaths", COLOR_DEATHS),
+		])));
 		let statelem = UiElem::new(
 			POS_STAT_GRIDS, 
 			HEIGHT_STAT_GRIDS, 
 			WIDTH_STAT_GRIDS  - x_offset(1),
 			"Stats".to_string(),
-			vec![
-				//Each frame defines where thier content lives witin the uielem
-				Frame::new(
-					POS_STAT_GRIDS,
-					y_offset(1), 
-					WIDTH_STAT_GRIDS - x_offset(1),
-					x_offset(1),
-					y_offset(1), 
-					"Generation : ".to_string(),
-					"",
-				),
-				Frame::new(
-					POS_STAT_GRIDS,
-					y_offset(1), 
-					WIDTH_STAT_GRIDS - x_offset(1),
-					x_offset(1),
-					y_offset(2), 
-					"Living         : ".to_string(),
-					"",
-				),
-				Frame::new(
-					POS_STAT_GRIDS,
-					y_offset(1), 
-					WIDTH_STAT_GRIDS - x_offset(1),
-					x_offset(1),
-					y_offset(3), 
-					"Fatalities   : ".to_string(),
-					"",
-				),
-			],
+			stats,
 		);
 
 		let optionelem = UiElem::new(
@@ -99,24 +267,19 @@ impl Game {
 			WIDTH_OPTION_GRIDS - x_offset(1), 
 			"Options".to_string(),
 			vec![
-				Frame::new(
-					POS_OPTION_GRIDS,
-					y_offset(1), 
-					WIDTH_STAT_GRIDS - x_offset(1),
-					x_offset(1),
-					y_offset(1), 
-					"Pause".to_string(),
-					"",
-				),
-				Frame::new(
-					POS_OPTION_GRIDS,
-					y_offset(1), 
-					WIDTH_STAT_GRIDS - x_offset(1),
-					x_offset(1),
-					y_offset(2), 
-					"Restart".to_string(),
-					"",
-				),
+				Box::new(Toggle::new(Control::Pause, "Pause", "Start")),
+				Box::new(Button::new(Control::Step, "Step")),
+				Box::new(Button::new(Control::StepMany, "Step 10")),
+				Box::new(TextInput::new(Control::StepCount, "N : ", "10")),
+				Box::new(Button::new(Control::RunUntilStable, "Run until stable")),
+				Box::new(Button::new(Control::Restart, "Restart")),
+				Box::new(Button::new(Control::Clear, "Clear")),
+				Box::new(Button::new(Control::Fit, "Fit pattern")),
+				Box::new(Checkbox::new(Control::Eraser, "Eraser", false)),
+				Box::new(Checkbox::new(Control::ShowChanges, "Show births / deaths", false)),
+				Box::new(Checkbox::new(Control::ShowTrails, "Show trails", false)),
+				Box::new(Checkbox::new(Control::ShowHeatmap, "Show heatmap", false)),
+				Box::new(Slider::new(Control::Speed, "Speed : ", 0.0, (SPEEDS.len() - 1) as f32, 0.0)),
 			],
 		);
 
@@ -126,17 +289,687 @@ impl Game {
 			WIDTH_ADVANCED_GRIDS - x_offset(1),
 			"Advanced Options".to_string(), 
 			vec![
+				Box::new(TextInput::new(Control::Rule, "Rule : ", Rule::classic().to_string().as_str())),
+				Box::new(TextInput::new(Control::Neighborhood, "Neighborhood : ", Rule::classic().neighborhood().name())),
+				Box::new(TextInput::new(Control::Population, "Population : ", "2000")),
+				Box::new(TextInput::new(Control::GridSize, "Grid size : ", format!("{}x{}", WIDTH_GAME_GRIDS, HEIGHT_GAME_GRIDS).as_str())),
+				Box::new(TextInput::new(Control::Seed, "Seed : ", "random")),
+				Box::new(TextInput::new(Control::SeedRow, "Seed row : ", Start::Single.to_string().as_str())),
+				Box::new(TextInput::new(Control::Boundary, "Boundary : ", Boundary::Dead.to_string().as_str())),
+				Box::new(TextInput::new(Control::ChartLength, "Chart length : ", CHART_LENGTH.to_string().as_str())),
+				Box::new(TextInput::new(Control::Record, "Record stats : ", "off")),
+				Box::new(Checkbox::new(Control::Ages, "Color by age", false)),
+			],
+		);
+
+		let patternelem = UiElem::new(
+			POS_PATTERN_GRIDS,
+			HEIGHT_PATTERN_GRIDS,
+			WIDTH_PATTERN_GRIDS - x_offset(1),
+			"Patterns".to_string(),
+			vec![
+				//Leave a line for the header and one below
+				Box::new(List::new(Control::Patterns, HEIGHT_PATTERN_GRIDS / HEIGHT_UI_LINE - 2, Vec::new())),
 			],
 		);
 
-		Game {
+		let minimapelem = UiElem::new(
+			POS_MINIMAP_GRIDS,
+			HEIGHT_MINIMAP_GRIDS,
+			WIDTH_MINIMAP_GRIDS - x_offset(1),
+			"Minimap".to_string(),
+			vec![
+				//Leave a line for the header and one below
+				Box::new(Minimap::new(Control::Minimap, HEIGHT_MINIMAP_GRIDS / HEIGHT_UI_LINE - 2)),
+			],
+		);
+
+		let mut game = Game {
 			game: World::new(2000),
 			option: optionelem,
 			stat: statelem,
 			advanced: advancedelem,
+			pattern: patternelem,
+			minimap: minimapelem,
+			minimap_drawn: None,
 			paused: false,
+			population: 2000,
+			size: (WIDTH_GAME_GRIDS, HEIGHT_GAME_GRIDS),
+			seed: None,
+			start: Start::Single,
+			ages: false,
+			overlays: Vec::new(),
+			clock: Clock::new(Speed::Rate(30.0)),
+			step_count: 10,
+			watch: None,
+			brush: Brush::Pen,
+			stroke: None,
+			camera: Camera::new((
+				(POS_GAME_GRIDS.0 * SIZE_GRID_PIXELS) as f32,
+				(POS_GAME_GRIDS.1 * SIZE_GRID_PIXELS) as f32,
+				(WIDTH_GAME_GRIDS * SIZE_GRID_PIXELS) as f32,
+				(HEIGHT_GAME_GRIDS * SIZE_GRID_PIXELS) as f32,
+			)),
+			panning: false,
+			cursor: None,
+			patterns: Vec::new(),
+			patterns_dir: None,
+			stamp: None,
+			selection: None,
+			selecting: None,
+			clipboard: None,
+			history: History::new(),
+			keymap: Keymap::new(),
+		};
+		game.sync_controls();
+		game
+	}
+
+	/// Replaces the pattern library with the patterns in a directory. Selections are saved there too.
+	///
+	/// # Arguments
+	/// * 'dir' - The directory holding the .rle files.
+	pub fn load_patterns(&mut self, dir: path::PathBuf) {
+		let (patterns, errors) = Pattern::load_dir(&dir);
+		for error in errors {
+			eprintln!("Error loading pattern: {}", error);
+		}
+		let names = patterns.iter().map(|pattern| pattern.name().to_string()).collect();
+		self.pattern.widget::<List<Control>>(Control::Patterns).set_items(names);
+		self.patterns = patterns;
+		self.patterns_dir = Some(dir);
+	}
+
+	/// Saves the selected cells to a new file in the pattern library, and shows the result under Status.
+	fn save_selection(&mut self) {
+		let copy = self.selection.and_then(|rect| self.game.copy(rect));
+		let (mut copy, dir) = match (copy, self.patterns_dir.clone()) {
+			(Some(copy), Some(dir)) => (copy, dir),
+			_ => return,
+		};
+
+		//Number the files so saving never replaces an earlier selection
+		let number = (1..).find(|n| !dir.join(format!("selection-{}.rle", n)).exists()).unwrap_or(0);
+		let name = format!("Selection {}", number);
+		copy.rename(name.as_str());
+		let status = match copy.save(&dir.join(format!("selection-{}.rle", number))) {
+			Ok(()) => {
+				self.load_patterns(dir);
+				format!("saved {}", name)
+			},
+			Err(error) => {
+				eprintln!("Error saving pattern: {}", error);
+				"save failed".to_string()
+			},
+		};
+		self.stat.set_text(Control::Status, status.as_str());
+	}
+
+	/// Saves a picture of the whole world, such as the diagram of a one dimensional rule, to a new
+	/// PNG file in the working directory, and shows the result under Status.
+	fn export_image(&mut self) {
+		let (width, height) = self.game.size();
+		let (width, height, rgba) = self.game.image((SIZE_EXPORT_PIXELS / width.max(height)).max(1));
+
+		//Number the files so exporting never replaces an earlier picture
+		let number = (1..).find(|n| !path::Path::new(&format!("life-{}.png", n)).exists()).unwrap_or(0);
+		let name = format!("life-{}.png", number);
+		let status = match save_png(path::Path::new(&name), width, height, &rgba) {
+			Ok(()) => format!("saved {}", name),
+			Err(error) => {
+				eprintln!("Error saving image: {}", error);
+				"export failed".to_string()
+			},
+		};
+		self.stat.set_text(Control::Status, status.as_str());
+	}
+
+	/// Rotates or flips the selected cells in place, or the picked pattern when nothing is selected.
+	///
+	/// # Arguments
+	/// * 'change' - Rotates or flips a pattern.
+	fn reshape(&mut self, change: fn(&mut Pattern)) {
+		match self.selection {
+			Some(rect) => self.selection = self.game.transform(rect, change),
+			None => if let Some(ref mut stamp) = self.stamp {
+				change(stamp);
+			},
+		}
+	}
+
+	/// Returns where the stamp lands when the mouse is over a cell, centered on the mouse.
+	///
+	/// # Arguments
+	/// * 'cell' - The cell under the mouse.
+	fn stamp_at(&self, cell: (i32, i32)) -> Option<(i32, i32)> {
+		self.stamp.as_ref().map(|stamp| {
+			let (width, height) = stamp.size();
+			(cell.0 - width / 2, cell.1 - height / 2)
+		})
+	}
+
+	/// Generates a world from the advanced options, following the current world's rule
+	fn new_world(&self) -> World {
+		self.new_world_under(self.game.rule().clone())
+	}
+
+	/// Generates a world from the advanced options under a given rule.
+	///
+	/// # Arguments
+	/// * 'rule' - The rule the world follows.
+	fn new_world_under(&self, rule: Rule) -> World {
+		//One dimensional rules grow from their first row instead of a population
+		let line = rule.line().is_some();
+		let mut world = World::generate(if line { 0 } else { self.population }, self.size, self.seed);
+		world.set_rule(rule);
+		if line {
+			world.start_line(self.start, self.seed);
+		}
+		world
+	}
+
+	/// Replaces the world, keeping the old one so the change can be undone.
+	///
+	/// # Arguments
+	/// * 'world' - The new world.
+	fn replace_world(&mut self, world: World) {
+		let mut old = mem::replace(&mut self.game, world);
+		//Recording and colors carry on with the new world
+		self.game.set_recorder(old.take_recorder());
+		self.game.set_gradient(old.gradient().clone());
+		self.refresh_view();
+		self.history.push(Edit::World(Box::new(old)));
+		self.stop_watching();
+		self.stat.widget::<Chart<Control>>(Control::Chart).clear_samples();
+	}
+
+	/// Runs one generation and plots it in the population chart
+	fn step(&mut self) {
+		let stats = self.game.update();
+		self.stat.widget::<Chart<Control>>(Control::Chart).push_sample(vec![stats.living as f64, stats.births as f64, stats.deaths as f64]);
+	}
+
+	/// Makes a change to the world's cells that is undone as one edit.
+	///
+	/// # Arguments
+	/// * 'change' - Edits cells of the world.
+	fn edit<F: FnOnce(&mut Self)>(&mut self, change: F) {
+		self.game.record();
+		change(self);
+		let changes = self.game.take_changes();
+		self.history.push(Edit::Cells(changes));
+		self.stop_watching();
+	}
+
+	/// Shows the world left by an undo or redo.
+	///
+	/// # Arguments
+	/// * 'generation' - The generation before the undo or redo.
+	fn restore(&mut self, generation: u64) {
+		self.stop_watching();
+		//A different generation means a different world, the chart no longer follows it
+		if self.game.stats().generation != generation {
+			self.stat.widget::<Chart<Control>>(Control::Chart).clear_samples();
+		}
+		self.show_rule();
+		self.refresh_view();
+		//The world may have changed size under the selection
+		self.selection = self.selection.and_then(|rect| self.game.clip(rect));
+	}
+
+	/// Makes the world track what the age colors and overlays picked in the panels need
+	fn refresh_view(&mut self) {
+		self.game.track_ages(self.ages);
+		for overlay in OVERLAYS.iter() {
+			self.game.show_overlay(*overlay, self.overlays.contains(overlay));
+		}
+	}
+
+	/// Shows or hides an overlay.
+	///
+	/// # Arguments
+	/// * 'overlay' - The overlay.
+	/// * 'shown'   - True to show it.
+	fn show_overlay(&mut self, overlay: Overlay, shown: bool) {
+		self.overlays.retain(|other| *other != overlay);
+		if shown {
+			self.overlays.push(overlay);
+		}
+		self.refresh_view();
+	}
+
+	/// Starts or stops recording stats, and shows where they are going in the advanced options.
+	///
+	/// # Arguments
+	/// * 'recorder' - Where to write the stats of each generation, None to stop.
+	fn record(&mut self, recorder: Option<Recorder>) {
+		let target = recorder.as_ref().map(|recorder| recorder.target().to_string());
+		self.game.set_recorder(recorder);
+		self.advanced.set_text(Control::Record, target.unwrap_or_else(|| "off".to_string()).as_str());
+		self.advanced.set_error(Control::Record, None);
+	}
+
+	/// Replaces the rule, keeping the old one for undoing.
+	///
+	/// # Arguments
+	/// * 'rule' - The new rule.
+	fn change_rule(&mut self, rule: Rule) {
+		if &rule != self.game.rule() {
+			let old = self.game.rule().clone();
+			self.game.set_rule(rule);
+			self.history.push(Edit::Rule(old));
+			self.stop_watching();
+		}
+		self.show_rule();
+	}
+
+	/// Shows the rule and the neighborhood it counts in the advanced options
+	fn show_rule(&mut self) {
+		let rule = self.game.rule().clone();
+		self.advanced.set_text(Control::Rule, rule.to_string().as_str());
+		self.advanced.set_text(Control::Neighborhood, rule.neighborhood().name());
+		self.advanced.set_text(Control::Boundary, rule.line().map_or(Boundary::Dead, |line| line.boundary()).to_string().as_str());
+		self.advanced.set_error(Control::Rule, None);
+		self.advanced.set_error(Control::Neighborhood, None);
+		self.advanced.set_error(Control::Boundary, None);
+	}
+
+	/// Applies a value committed in the advanced options. Valid values replace the
+	/// rule straight away, and restart the world for everything else. Invalid values
+	/// leave the game alone and show an error next to the input.
+	///
+	/// # Arguments
+	/// * 'control' - The input the value was committed in.
+	/// * 'text'    - The committed value.
+	fn apply_setting(&mut self, control: Control, text: &str) {
+		let area = |size: (i32, i32)| { size.0 * size.1 };
+		let text = text.trim();
+		let result = match control {
+			//Switching to a one dimensional rule starts its diagram from the seed row
+			//The replaced world keeps the old rule, so one undo brings back both
+			Control::Rule => Rule::parse(text).map(|rule| {
+				if rule.line().is_some() && self.game.rule().line().is_none() {
+					let world = self.new_world_under(rule);
+					self.replace_world(world);
+					self.show_rule();
+				}
+				else {
+					self.change_rule(rule);
+				}
+			}),
+			Control::Boundary => Boundary::parse(text)
+				.and_then(|boundary| self.game.rule().with_boundary(boundary))
+				.map(|rule| self.change_rule(rule)),
+			Control::SeedRow => Start::parse(text).map(|start| {
+				self.start = start;
+				self.advanced.set_text(control, start.to_string().as_str());
+				if self.game.rule().line().is_some() {
+					let world = self.new_world();
+					self.replace_world(world);
+				}
+			}),
+			//A built in shape by name, or a file holding one
+			Control::Neighborhood => Neighborhood::builtin(text)
+				.map_or_else(|| Neighborhood::load(text), Ok)
+				.and_then(|neighborhood| self.game.rule().with_neighborhood(neighborhood))
+				.map(|rule| self.change_rule(rule)),
+			Control::Population => match text.parse::<i32>() {
+				Ok(population) if population >= 0 && population <= area(self.size) => {
+					self.population = population;
+					let world = self.new_world();
+					self.replace_world(world);
+					Ok(())
+				},
+				_ => Err(format!("0 to {}", area(self.size))),
+			},
+			Control::GridSize => {
+				let size: Vec<Option<i32>> = text.split('x').map(|n| n.trim().parse::<i32>().ok()).collect();
+				match size.as_slice() {
+					&[Some(width), Some(height)] if (2..=MAX_WORLD_GRIDS).contains(&width) && (2..=MAX_WORLD_GRIDS).contains(&height) => {
+						self.size = (width, height);
+						self.advanced.set_text(control, format!("{}x{}", width, height).as_str());
+
+						//A smaller map may not fit the population
+						if self.population > area(self.size) {
+							self.population = area(self.size);
+							self.advanced.set_text(Control::Population, self.population.to_string().as_str());
+							self.advanced.set_error(Control::Population, None);
+						}
+						let world = self.new_world();
+						self.replace_world(world);
+						self.camera.fit((0, 0, width, height));
+						Ok(())
+					},
+					_ => Err(format!("2x2 to {}x{}", MAX_WORLD_GRIDS, MAX_WORLD_GRIDS)),
+				}
+			},
+			Control::StepCount => match text.parse::<u32>() {
+				Ok(count) if (1..=MAX_STEP_COUNT).contains(&count) => {
+					self.step_count = count;
+					self.option.set_text(control, count.to_string().as_str());
+					self.option.set_text(Control::StepMany, format!("Step {}", count).as_str());
+					Ok(())
+				},
+				_ => Err(format!("1 to {}", MAX_STEP_COUNT)),
+			},
+			Control::ChartLength => match text.parse::<usize>() {
+				Ok(length) if (2..=MAX_CHART_LENGTH).contains(&length) => {
+					self.stat.widget::<Chart<Control>>(Control::Chart).set_length(length);
+					self.advanced.set_text(control, length.to_string().as_str());
+					Ok(())
+				},
+				_ => Err(format!("2 to {}", MAX_CHART_LENGTH)),
+			},
+			Control::Seed => match text {
+				"" | "random" => {
+					self.seed = None;
+					self.advanced.set_text(control, "random");
+					let world = self.new_world();
+					self.replace_world(world);
+					Ok(())
+				},
+				_ => match text.parse::<u64>() {
+					Ok(seed) => {
+						self.seed = Some(seed);
+						let world = self.new_world();
+						self.replace_world(world);
+						Ok(())
+					},
+					Err(_) => Err("a number or random".to_string()),
+				},
+			},
+			Control::Record => match text {
+				"" | "off" => {
+					self.record(None);
+					Ok(())
+				},
+				_ => Recorder::open(text, None).map(|recorder| self.record(Some(recorder))),
+			},
+			_ => Ok(()),
+		};
+
+		//Only the panel holding the input shows the error
+		self.option.set_error(control, result.clone().err());
+		self.advanced.set_error(control, result.err());
+		self.refresh_stats();
+	}
+
+	/// Shows the current pause state, brush and speed in the option element
+	fn sync_controls(&mut self) {
+		let speed = self.clock.speed();
+		self.option.set_checked(Control::Pause, self.paused);
+		self.option.set_checked(Control::Eraser, self.brush == Brush::Eraser);
+		self.option.set_value(Control::Speed, speed.level() as f32);
+		self.option.set_text(Control::Speed, speed.to_string().as_str());
+	}
+
+	/// Carries out an action requested by the user.
+	///
+	/// # Arguments
+	/// * 'action' - The requested action.
+	fn perform(&mut self, action: Action) {
+		match action {
+			Action::Pause => self.set_paused(!self.paused),
+			Action::Step => {
+				self.set_paused(true);
+				self.step();
+			},
+			Action::StepMany => {
+				self.set_paused(true);
+				for _ in 0..self.step_count {
+					self.step();
+				}
+			},
+			Action::RunUntilStable => {
+				let mut watch = StabilityWatch::new();
+				match watch.check(&self.game) {
+					Some(settled) => self.settle(settled),
+					None => {
+						self.paused = false;
+						self.watch = Some(watch);
+						self.stat.set_text(Control::Status, "running until stable");
+					},
+				}
+			},
+			Action::Restart => {
+				let world = self.new_world();
+				self.replace_world(world);
+			},
+			Action::Clear => {
+				let mut world = World::generate(0, self.size, None);
+				world.set_rule(self.game.rule().clone());
+				self.replace_world(world);
+			},
+			Action::SpeedUp => self.clock.speed_up(),
+			Action::SpeedDown => self.clock.slow_down(),
+			//Panning left brings cells to the left into view
+			Action::PanLeft => self.camera.pan(PAN_PIXELS, 0.0),
+			Action::PanRight => self.camera.pan(-PAN_PIXELS, 0.0),
+			Action::PanUp => self.camera.pan(0.0, PAN_PIXELS),
+			Action::PanDown => self.camera.pan(0.0, -PAN_PIXELS),
+			Action::Fit => {
+				//Show the whole world when there is no pattern to fit
+				let (width, height) = self.game.size();
+				self.camera.fit(self.game.bounds().unwrap_or((0, 0, width, height)));
+			},
+			Action::Rotate => self.edit(|game| game.reshape(Pattern::rotate)),
+			Action::FlipHorizontal => self.edit(|game| game.reshape(Pattern::flip_horizontal)),
+			Action::FlipVertical => self.edit(|game| game.reshape(Pattern::flip_vertical)),
+			Action::Copy => if let Some(copy) = self.selection.and_then(|rect| self.game.copy(rect)) {
+				self.clipboard = Some(copy);
+			},
+			Action::Cut => {
+				self.perform(Action::Copy);
+				self.perform(Action::ClearSelection);
+			},
+			//The pasted cells are left selected, so they can be moved on with a cut and paste
+			Action::Paste => self.edit(|game| if let (Some(clipboard), Some(cell)) = (game.clipboard.as_ref(), game.cursor) {
+				let (width, height) = clipboard.size();
+				game.game.stamp(clipboard, cell);
+				game.brush = Brush::Select;
+				game.selection = game.game.clip((cell.0, cell.1, width, height));
+			}),
+			Action::ClearSelection => self.edit(|game| if let Some(rect) = game.selection {
+				game.game.fill(rect, false);
+			}),
+			Action::FillRandom => self.edit(|game| if let Some(rect) = game.selection {
+				game.game.scatter(rect, FILL_DENSITY, None);
+			}),
+			Action::SaveSelection => self.save_selection(),
+			Action::ExportImage => self.export_image(),
+			Action::NextColor => if let Some(color) = self.game.next_paint() {
+				self.stat.set_text(Control::Status, format!("painting color {}", color + 1).as_str());
+			},
+			//Wait for a stroke to finish, so it isn't mixed up with the undo
+			Action::Undo => {
+				let generation = self.game.stats().generation;
+				if self.stroke.is_none() && self.history.undo(&mut self.game) {
+					self.restore(generation);
+				}
+			},
+			Action::Redo => {
+				let generation = self.game.stats().generation;
+				if self.stroke.is_none() && self.history.redo(&mut self.game) {
+					self.restore(generation);
+				}
+			},
+			//There is nothing to stamp until a pattern is picked
+			Action::Brush(Brush::Stamp) if self.stamp.is_none() => (),
+			Action::Brush(brush) => {
+				if brush != Brush::Select {
+					self.selection = None;
+				}
+				self.brush = brush;
+			},
+		}
+		self.sync_controls();
+		self.refresh_stats();
+	}
+
+	/// Pauses or starts the world. Either way, the user has taken over from run until stable.
+	///
+	/// # Arguments
+	/// * 'paused' - True to pause the world.
+	fn set_paused(&mut self, paused: bool) {
+		self.paused = paused;
+		self.stop_watching();
+	}
+
+	/// Stops running until stable without a result
+	fn stop_watching(&mut self) {
+		if self.watch.take().is_some() {
+			self.stat.set_text(Control::Status, "");
 		}
 	}
+
+	/// Pauses a world that stopped changing while running until stable.
+	///
+	/// # Arguments
+	/// * 'settled' - How the world stopped changing.
+	fn settle(&mut self, settled: Settled) {
+		self.paused = true;
+		self.watch = None;
+		let status = format!("{} at {}", settled, self.game.stats().generation);
+		self.stat.set_text(Control::Status, status.as_str());
+		self.sync_controls();
+	}
+
+	/// Redraws the minimap picture if it is due, and moves its marker to the camera.
+	fn refresh_minimap(&mut self) {
+		let due = match self.minimap_drawn {
+			Some(drawn) => drawn.elapsed() >= MINIMAP_INTERVAL,
+			None => true,
+		};
+		if due {
+			let (width, height, rgba) = self.game.thumbnail(SIZE_MINIMAP_PIXELS);
+			self.minimap.widget::<Minimap<Control>>(Control::Minimap).set_image(width, height, rgba);
+			self.minimap_drawn = Some(Instant::now());
+		}
+
+		//The marker is cheap, so it keeps up with the camera every frame
+		let (width, height) = self.game.size();
+		let (vx, vy, vw, vh) = self.camera.viewport();
+		let start = self.camera.to_world(vx, vy);
+		let end = self.camera.to_world(vx + vw, vy + vh);
+		self.minimap.widget::<Minimap<Control>>(Control::Minimap).set_marker((
+			start.0 / width as f32,
+			start.1 / height as f32,
+			(end.0 - start.0) / width as f32,
+			(end.1 - start.1) / height as f32,
+		));
+	}
+
+	/// Carries out a change the user made to a widget.
+	///
+	/// # Arguments
+	/// * 'event' - The change.
+	fn widget_event(&mut self, event: WidgetEvent<Control>) {
+		match event {
+			WidgetEvent::Toggled(Control::Pause, paused) => self.set_paused(paused),
+			WidgetEvent::Pressed(Control::Step) => self.perform(Action::Step),
+			WidgetEvent::Pressed(Control::StepMany) => self.perform(Action::StepMany),
+			WidgetEvent::Pressed(Control::RunUntilStable) => self.perform(Action::RunUntilStable),
+			WidgetEvent::Pressed(Control::Restart) => self.perform(Action::Restart),
+			WidgetEvent::Pressed(Control::Clear) => self.perform(Action::Clear),
+			WidgetEvent::Pressed(Control::Fit) => self.perform(Action::Fit),
+			WidgetEvent::Toggled(Control::Ages, ages) => {
+				self.ages = ages;
+				self.refresh_view();
+			},
+			WidgetEvent::Toggled(Control::ShowChanges, shown) => self.show_overlay(Overlay::Changes, shown),
+			WidgetEvent::Toggled(Control::ShowTrails, shown) => self.show_overlay(Overlay::Trails, shown),
+			WidgetEvent::Toggled(Control::ShowHeatmap, shown) => self.show_overlay(Overlay::Heatmap, shown),
+			WidgetEvent::Toggled(Control::Eraser, eraser) => {
+				self.perform(Action::Brush(if eraser { Brush::Eraser } else { Brush::Pen }));
+			},
+			WidgetEvent::Changed(Control::Speed, level) => {
+				self.clock.set_speed(SPEEDS[level.round() as usize]);
+				self.sync_controls();
+			},
+			WidgetEvent::Committed(control, text) => self.apply_setting(control, text.as_str()),
+			WidgetEvent::Selected(Control::Patterns, index) => {
+				self.stamp = self.patterns.get(index).cloned();
+				//WireWorld circuits don't mean anything under other rules, so they bring their rule
+				let rule = self.stamp.as_ref().and_then(|stamp| stamp.rule()).and_then(|rule| Rule::parse(rule).ok());
+				if let Some(rule) = rule.filter(|rule| rule.is_wireworld()) {
+					self.change_rule(rule);
+				}
+				self.perform(Action::Brush(Brush::Stamp));
+			},
+			WidgetEvent::Picked(Control::Minimap, x, y) => {
+				let (width, height) = self.game.size();
+				self.camera.center_on(x * width as f32, y * height as f32);
+			},
+			_ => (),
+		}
+	}
+
+	/// Sends user input to every side panel and carries out the changes they report.
+	///
+	/// # Arguments
+	/// * 'event' - The input.
+	fn ui_event(&mut self, event: &UiEvent) {
+		let changes = vec![
+			self.stat.handle_event(event),
+			self.option.handle_event(event),
+			self.advanced.handle_event(event),
+			self.pattern.handle_event(event),
+			self.minimap.handle_event(event),
+		];
+		for change in changes.into_iter().flatten() {
+			self.widget_event(change);
+		}
+	}
+
+	/// Returns true if a side panel is taking keyboard input
+	fn ui_focused(&self) -> bool {
+		self.stat.focused() || self.option.focused() || self.advanced.focused() || self.pattern.focused() || self.minimap.focused()
+	}
+
+	/// Shows the world's current meta data in the stats element
+	fn refresh_stats(&mut self) {
+		let stats = self.game.stats();
+		let living = format!("{} ({:.1}%)", stats.living, stats.density * 100.0);
+		let colors = match self.game.rule().colors() {
+			0 => "none".to_string(),
+			count => stats.colors[..count as usize].iter().map(|living| living.to_string()).collect::<Vec<String>>().join(" / "),
+		};
+		let peak = format!("{} at {}", stats.peak, stats.peak_generation);
+		let changes = format!("{} / {}", stats.births, stats.deaths);
+		let totals = format!("{} / {}", stats.total_births, stats.total_deaths);
+		let bounds = match stats.bounds {
+			Some((x, y, width, height)) => format!("{}x{} at {}, {}", width, height, x, y),
+			None => "none".to_string(),
+		};
+		self.stat.set_text(Control::Generation, stats.generation.to_string().as_str());
+		self.stat.set_text(Control::Living, living.as_str());
+		self.stat.set_text(Control::Colors, colors.as_str());
+		self.stat.set_text(Control::Peak, peak.as_str());
+		self.stat.set_text(Control::Changes, changes.as_str());
+		self.stat.set_text(Control::Totals, totals.as_str());
+		self.stat.set_text(Control::Bounds, bounds.as_str());
+		self.refresh_cursor();
+	}
+
+	/// Shows the cell under the mouse in the stats element, with its age while ages are tracked
+	fn refresh_cursor(&mut self) {
+		let cell = self.cursor.and_then(|(x, y)| self.game.get_cell(x, y).map(|alive| {
+			let color = self.game.color(x, y).map_or(String::new(), |color| format!(", color {}", color + 1));
+			match (alive, self.game.age(x, y)) {
+				(true, Some(age)) => format!("{}, {}, age {}{}", x, y, age, color),
+				(true, None) => format!("{}, {}, alive{}", x, y, color),
+				(false, _) => format!("{}, {}, dead", x, y),
+			}
+		}));
+		self.stat.set_text(Control::Cell, cell.unwrap_or_else(|| "none".to_string()).as_str());
+	}
+
+	/// Shows how fast generations and frames are really running in the stats element
+	fn refresh_timing(&mut self) {
+		let behind = if self.clock.behind() { ", behind" } else { "" };
+		let timing = format!("{:.1} gen/s, {:.1} ms{}", self.clock.generations_per_second(), self.clock.frame_time() * 1000.0, behind);
+		self.stat.set_text(Control::Timing, timing.as_str());
+	}
 }
 
 
@@ -146,21 +979,32 @@ impl event::EventHandler for Game{
 
 	/// Mandatory override of [EventHandler::update()](https://docs.rs/ggez/0.3.1/ggez/event/trait.EventHandler.html#tymethod.update)
 	///
-	/// Updates all game elements
-	fn update(&mut self, _ctx: &mut Context) -> GameResult<()> {
+	/// Runs the generations due since the last frame at the target speed
+	fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+		let seconds = timer::duration_to_f64(timer::get_delta(ctx));
 
+		let mut generations = 0;
 		if !self.paused {
-			let generation = self.game.update();
-			self.stat.update(Some(
-						vec![
-							generation.0.to_string(),
-							generation.1.to_string(),
-							generation.2.to_string()
-						]
-					));
-		}
-		self.option.update(None);
-		self.advanced.update(None);
+			let due = self.clock.advance(seconds);
+			let start = Instant::now();
+			while generations < due && start.elapsed() < MAX_UPDATE_TIME {
+				self.step();
+				generations += 1;
+
+				let game = &self.game;
+				let settled = self.watch.as_mut().and_then(|watch| watch.check(game));
+				if let Some(settled) = settled {
+					self.settle(settled);
+					break;
+				}
+			}
+			if generations > 0 {
+				self.refresh_stats();
+			}
+		}
+		self.clock.record(seconds, generations);
+		self.refresh_timing();
+		self.refresh_minimap();
 		Ok(())//Update for game-over scenario?
 	}
 
@@ -169,47 +1013,278 @@ impl event::EventHandler for Game{
 	/// Draws all game elements
 	fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
 		graphics::clear(ctx);
-		self.game.draw(ctx);
-		self.option.draw(ctx);
-		self.stat.draw(ctx);
-		self.advanced.draw(ctx);
+		{
+			let renderer = &mut GgezRenderer::new(ctx);
+			self.game.draw(renderer, &self.camera);
+			if let Some((x, y, width, height)) = self.selection {
+				let (px, py) = self.camera.to_screen(x as f32, y as f32);
+				let zoom = self.camera.zoom();
+				if let Some((px, py, w, h)) = self.camera.clip((px, py, width as f32 * zoom, height as f32 * zoom)) {
+					renderer.set_color(COLOR_SELECTION);
+					renderer.fill_rect(px, py, w, h);
+				}
+			}
+			if let (Brush::Stamp, Some(cell)) = (self.brush, self.cursor) {
+				if let (Some(stamp), Some(at)) = (self.stamp.as_ref(), self.stamp_at(cell)) {
+					stamp.draw(renderer, &self.camera, at, COLOR_PREVIEW, self.game.rule().neighborhood().is_hex());
+				}
+			}
+			self.option.draw(renderer);
+			self.stat.draw(renderer);
+			self.advanced.draw(renderer);
+			self.pattern.draw(renderer);
+			self.minimap.draw(renderer);
+		}
 		graphics::present(ctx);
 		Ok(())
 	}
 
+	/// Optional override of [EventHandler::mouse_button_down_event()](https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.mouse_button_down_event)
+	///
+	/// Starts editing the cell under the mouse with the current brush, starts a selection, or starts
+	/// dragging the camera with the middle button or hand. Presses outside the game area go to the side panels.
+	fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: i32, y: i32) {
+		if !self.camera.contains(x as f32, y as f32) {
+			if button == MouseButton::Left {
+				self.ui_event(&UiEvent::Press(x, y));
+			}
+			return;
+		}
+
+		match (button, self.brush) {
+			(MouseButton::Middle, _) | (MouseButton::Left, Brush::Hand) => self.panning = true,
+			//Patterns may hang off the edge of the map, so the mouse doesn't need to be over it
+			(MouseButton::Left, Brush::Stamp) => {
+				let at = self.game.cell_at(&self.camera, x, y).and_then(|cell| self.stamp_at(cell));
+				self.edit(|game| if let (Some(stamp), Some(at)) = (game.stamp.as_ref(), at) {
+					game.game.stamp(stamp, at);
+				});
+				self.refresh_stats();
+			},
+			//Pressing off the map drops the selection
+			(MouseButton::Left, Brush::Select) => {
+				self.selecting = self.game.grid_at(&self.camera, x, y);
+				self.selection = self.selecting.map(|cell| (cell.0, cell.1, 1, 1));
+			},
+			_ => if let Some(cell) = self.game.grid_at(&self.camera, x, y) {
+				//Everything the stroke changes is undone as one edit
+				match (button, self.brush) {
+					(MouseButton::Left, Brush::Pen) => {
+						self.stop_watching();
+						self.game.record();
+						self.stroke = Some(Stroke { state: self.game.rule().painted(), last: cell, dragged: false });
+					},
+					(MouseButton::Left, Brush::Eraser) | (MouseButton::Right, _) => {
+						self.stop_watching();
+						self.game.record();
+						self.game.set_cell(cell.0, cell.1, false);
+						self.stroke = Some(Stroke { state: DEAD, last: cell, dragged: true });
+						self.refresh_stats();
+					},
+					(MouseButton::Left, Brush::Electron) => {
+						self.edit(|game| game.game.spark(cell.0, cell.1));
+						self.refresh_stats();
+					},
+					_ => (),
+				}
+			},
+		}
+	}
+
+	/// Optional override of [EventHandler::mouse_motion_event()](https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.mouse_motion_event)
+	///
+	/// Drags the camera, stretches the selection, paints or erases every cell the mouse is dragged over,
+	/// or passes the drag to the side panels.
+	fn mouse_motion_event(&mut self, _ctx: &mut Context, state: MouseState, x: i32, y: i32, xrel: i32, yrel: i32) {
+		self.cursor = self.game.cell_at(&self.camera, x, y);
+		self.refresh_cursor();
+
+		if self.panning {
+			self.camera.pan(xrel as f32, yrel as f32);
+			return;
+		}
+
+		//The mouse may be past the edge of the map, so the selection is clipped to it
+		if let Some(start) = self.selecting {
+			if let Some(cell) = self.cursor {
+				self.selection = self.game.clip(span(start, cell));
+			}
+			return;
+		}
+
+		if self.stroke.is_none() {
+			if state.left() {
+				self.ui_event(&UiEvent::Drag(x, y));
+			}
+			return;
+		}
+
+		let cell = match self.game.grid_at(&self.camera, x, y) {
+			Some(cell) => cell,
+			None => return,
+		};
+
+		if let Some(ref mut stroke) = self.stroke {
+			if cell != stroke.last {
+				self.game.set_line_state(stroke.last, cell, stroke.state);
+				stroke.last = cell;
+				stroke.dragged = true;
+			}
+		}
+		self.refresh_stats();
+	}
+
 	/// Optional override of [EventHandler::mouse_button_up_event()](https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.mouse_button_up_event)
 	///
 	/// Pass mouse click data to game elements
-	fn mouse_button_up_event(&mut self, ctx: &mut Context, _button: MouseButton, x: i32, y: i32){
+	fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: i32, y: i32){
 	//https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.mouse_button_up_event
-		match self.option.mouse_click(x, y) {
-			Some(string) => {
-				match string.as_str() {
-					"Pause" => { self.paused = true; self.draw(ctx).expect("Error drawing game"); },
-					"Start" => { self.paused = false; self.draw(ctx).expect("Error drawing game"); },
-					"Restart" => { 
-						self.game = World::new(2000);
-						self.stat.update(Some(vec!["0".to_string(), "2000".to_string(), "0".to_string()]));
-						self.draw(ctx).expect("Error drawing game"); 
-					},
-					&_ => {},
-				}
-			}, 
-			None => match self.stat.mouse_click(x, y) {
-				Some(_string) => {},
-				None => match self.advanced.mouse_click(x, y) {
-					Some(_string) => {},
-					None => (),
-				}
+		self.panning = false;
+		self.selecting = None;
+
+		//Finish editing the grid. A left click that never dragged toggles its cell.
+		if let Some(stroke) = self.stroke.take() {
+			if stroke.state != DEAD && !stroke.dragged {
+				self.game.toggle_cell(stroke.last.0, stroke.last.1);
+				self.refresh_stats();
+			}
+			let changes = self.game.take_changes();
+			self.history.push(Edit::Cells(changes));
+			self.stop_watching();
+		}
+
+		//Panels see every click, so clicking anywhere else takes focus from a text input
+		if button == MouseButton::Left {
+			self.ui_event(&UiEvent::Click(x, y));
+		}
+	}
+
+	/// Optional override of [EventHandler::mouse_wheel_event()](https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.mouse_wheel_event)
+	///
+	/// Zooms the camera around the mouse, or scrolls the side panel under it.
+	fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: i32, y: i32) {
+		let position = mouse::get_position(ctx).expect("Error finding mouse");
+		if self.camera.contains(position.x, position.y) {
+			self.camera.zoom_at(position.x, position.y, ZOOM_STEP.powi(y));
+		}
+		else {
+			self.ui_event(&UiEvent::Scroll(position.x as i32, position.y as i32, y));
+		}
+	}
+
+	/// Optional override of [EventHandler::key_down_event()](https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.key_down_event)
+	///
+	/// Performs the action bound to a key. Escape quits.
+	/// While a text input has focus, keys edit it instead.
+	fn key_down_event(&mut self, ctx: &mut Context, keycode: Keycode, keymod: Mod, repeat: bool) {
+		if self.ui_focused() {
+			self.ui_event(&UiEvent::Key(keycode));
+			return;
+		}
+
+		if keycode == Keycode::Escape {
+			ctx.quit().expect("Error quitting game");
+			return;
+		}
+
+		let ctrl = keymod.intersects(LCTRLMOD | RCTRLMOD);
+		if let Some(action) = self.keymap.action(keycode, ctrl) {
+			//Holding a key down only repeats actions that make sense to repeat
+			let repeats = matches!(action,
+				Action::Step | Action::StepMany | Action::SpeedUp | Action::SpeedDown |
+				Action::PanLeft | Action::PanRight | Action::PanUp | Action::PanDown |
+				Action::Undo | Action::Redo);
+			if !repeat || repeats {
+				self.perform(action);
 			}
 		}
 	}
+
+	/// Optional override of [EventHandler::text_input_event()](https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.text_input_event)
+	///
+	/// Types into the focused text input.
+	fn text_input_event(&mut self, _ctx: &mut Context, text: String) {
+		if self.ui_focused() {
+			self.ui_event(&UiEvent::Text(text.as_str()));
+		}
+	}
+
+	/// Optional override of [EventHandler::quit_event()](https://docs.rs/ggez/0.4.3/ggez/event/trait.EventHandler.html#method.quit_event)
+	///
+	/// Stops recording stats when the window is closed, so the last records are saved.
+	fn quit_event(&mut self, _ctx: &mut Context) -> bool {
+		self.game.set_recorder(None);
+		false
+	}
+}
+
+/// Returns the block of cells between two corners, both included.
+///
+/// # Arguments
+/// * 'a & b' - Opposite corners of the block.
+///
+/// # Return
+/// * (i32, i32, i32, i32) - The block as (x, y, width, height).
+fn span(a: (i32, i32), b: (i32, i32)) -> (i32, i32, i32, i32) {
+	(a.0.min(b.0), a.1.min(b.1), (a.0 - b.0).abs() + 1, (a.1 - b.1).abs() + 1)
+}
+
+///How to start the game, shown when the command line can't be read
+const USAGE: &str = "Usage: cargo run [-- [--record <file|-> [--record-format csv|jsonl]] [--age-colors <age:RRGGBB,...>]]";
+
+///Options read from the command line
+struct Args {
+	///Records stats from the first generation, None if not recording
+	recorder: Option<Recorder>,
+	///Colors for cells by age, None to keep the default colors
+	age_colors: Option<Gradient>,
+}
+
+/// Reads the options from the command line:
+/// * '--record <file>' records stats to a file, or to stdout when the file is "-"
+/// * '--record-format <csv|jsonl>' picks the stats format, otherwise it comes from the file name
+/// * '--age-colors <age:RRGGBB,...>' picks the colors cells are given by age
+///
+/// # Return
+/// * Result<Args, String> - The options, or what was wrong with them.
+fn parse_args() -> Result<Args, String> {
+	let mut target = None;
+	let mut format = None;
+	let mut age_colors = None;
+	let mut args = env::args().skip(1);
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--age-colors" => {
+				let text = args.next().ok_or("--age-colors needs colors, such as 1:f2991a,100:663399")?;
+				age_colors = Some(Gradient::parse(text.as_str())?);
+			},
+			"--record" => target = Some(args.next().ok_or("--record needs a file, or - for stdout")?),
+			"--record-format" => {
+				let name = args.next().ok_or("--record-format needs csv or jsonl")?;
+				format = Some(Format::from_name(name.as_str()).ok_or_else(|| format!("Unknown stats format: {}", name))?);
+			},
+			_ => return Err(format!("Unknown option: {}", arg)),
+		}
+	}
+
+	let recorder = match target {
+		Some(target) => Some(Recorder::open(target.as_str(), format)?),
+		None if format.is_some() => return Err("--record-format needs --record".to_string()),
+		None => None,
+	};
+	Ok(Args { recorder, age_colors })
 }
 
 fn main() {
+	//Read the options before opening a window, so bad ones fail fast
+	let args = match parse_args() {
+		Ok(args) => args,
+		Err(error) => panic!("{}\n{}", error, USAGE),
+	};
+
 	//Check cargo manifest directory for external .ttf files
 	if let Ok(cargo_manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
-		let mut path_buffer = path::PathBuf::from(cargo_manifest_dir);
+		let mut path_buffer = path::PathBuf::from(cargo_manifest_dir.clone());
 		path_buffer.push("ttf");
 
 		//Build program
@@ -225,14 +1300,74 @@ fn main() {
 		//Build the game
 		let game = &mut Game::classic(); 
 
+		//Load the pattern library from the patterns directory
+		let mut patterns_path = path::PathBuf::from(cargo_manifest_dir.clone());
+		patterns_path.push("patterns");
+		game.load_patterns(patterns_path);
+
+		//Record stats from the first generation if asked to on the command line
+		if args.recorder.is_some() {
+			game.record(args.recorder);
+		}
+		if let Some(gradient) = args.age_colors {
+			game.game.set_gradient(gradient);
+		}
+
+		//Replace default key bindings with any found in keys.cfg
+		let mut keys_path = path::PathBuf::from(cargo_manifest_dir);
+		keys_path.push("keys.cfg");
+		if keys_path.exists() {
+			match Keymap::load(&keys_path) {
+				Ok(keymap) => game.keymap = keymap,
+				Err(error) => eprintln!("Error loading key bindings: {}", error),
+			}
+		}
+
 		//Run the main game loop
-		match event::run(window, game){
 		//https://docs.rs/ggez/0.3.0/ggez/event/fn.run.html
-			Ok(_) => println!("Copyright 2016 Matthew Greenlaw. Download from: https://github.com/MatthewGreenlaw/GameOfLife"),
-			Err(error) => println!("Error running game: {:?}", error),
+		let result = event::run(window, game);
+
+		//Save the last records however the game ended, before anything else is printed,
+		//since stats may be recorded to standard output
+		game.game.set_recorder(None);
+		match result {
+			Ok(_) => eprintln!("Copyright 2016 Matthew Greenlaw. Download from: https://github.com/MatthewGreenlaw/GameOfLife"),
+			Err(error) => eprintln!("Error running game: {:?}", error),
 		}
 	}
 	else {
-		panic!("Usage: cargo run");
+		panic!("{}", USAGE);
 	}
-}
\ No newline at end of file
+}
+#[test]
+fn test_main_edit_stops_watching() {
+	//A blinker being run until stable
+	let mut locals = vec![vec![false; 6]; 6];
+	locals[2][1] = true;
+	locals[2][2] = true;
+	locals[2][3] = true;
+	let mut game = Game::classic();
+	game.game = World::set_all(locals, 0, 3, 0);
+	game.perform(Action::RunUntilStable);
+	game.step();
+	let world = &game.game;
+	assert_eq!(None, game.watch.as_mut().and_then(|watch| watch.check(world)));
+
+	//Drawing it back to its first phase would have looked like a still life to the old watch
+	game.edit(|game| {
+		game.game.set_cell(1, 2, false);
+		game.game.set_cell(3, 2, false);
+		game.game.set_cell(2, 1, true);
+		game.game.set_cell(2, 3, true);
+	});
+	assert!(game.watch.is_none());
+	game.perform(Action::RunUntilStable);
+	game.step();
+	let world = &game.game;
+	assert_eq!(None, game.watch.as_mut().and_then(|watch| watch.check(world)));
+
+	//A new rule makes the old generations meaningless too
+	game.perform(Action::RunUntilStable);
+	game.change_rule(Rule::parse("B36/S23").unwrap());
+	assert!(game.watch.is_none());
+}
@@ -0,0 +1,306 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Neighborhoods. A Neighborhood is the set of cells, relative to a cell, whose
+// living cells count toward its neighbors, each with a weight. Shapes are either
+// built in or read from a small text format, a grid with the cell in the middle:
+//
+//     # Lines starting with # are comments
+//     . 1 .
+//     1 . 1
+//     . 1 .
+//
+// '.' is not a neighbor and the digits 1 to 9 are neighbors counted that many
+// times. Spaces are ignored, and the grid must have an odd width and height so it
+// has a middle. A line reading "hex" before the grid lays the shape on a hex grid,
+// where the rows of the grid are stored offset like the rows of the world.
+
+use std::fs;
+use std::path::Path;
+
+use rule::{Range, State, ALIVE, MAX_COLORS};
+
+///Cells counted around a cell, and how much each counts
+#[derive(Clone, Debug, PartialEq)]
+pub struct Neighborhood {
+	///Shown to the user, and written after B/S rules as V or H for the built in shapes that have a letter
+	name: String,
+	///Each neighbor as (dx, dy, weight) from the cell. On hex grids these are for cells in even rows.
+	offsets: Vec<(i32, i32, i32)>,
+	///True if the shape is laid on a hex grid, where odd rows sit half a cell right of even rows
+	hex: bool,
+}
+
+impl Neighborhood {
+	/// Generates the eight cells around a cell, the shape of the classic Game of Life
+	pub fn moore() -> Self {
+		Neighborhood::range(Range::Moore, 1, false)
+	}
+
+	/// Generates the four cells sharing an edge with a cell
+	pub fn von_neumann() -> Self {
+		Neighborhood::range(Range::VonNeumann, 1, false)
+	}
+
+	/// Generates the six cells around a cell on a hex grid
+	pub fn hexagonal() -> Self {
+		Neighborhood {
+			name: "hex".to_string(),
+			offsets: vec![(-1, -1, 1), (0, -1, 1), (-1, 0, 1), (1, 0, 1), (-1, 1, 1), (0, 1, 1)],
+			hex: true,
+		}
+	}
+
+	/// Generates the cells up to two away along the row and column of a cell, a plus sign
+	pub fn cross() -> Self {
+		Neighborhood {
+			name: "cross".to_string(),
+			offsets: vec![(0, -2, 1), (0, -1, 1), (-2, 0, 1), (-1, 0, 1), (1, 0, 1), (2, 0, 1), (0, 1, 1), (0, 2, 1)],
+			hex: false,
+		}
+	}
+
+	/// Generates the cells within a Larger than Life range.
+	///
+	/// # Arguments
+	/// * 'range'  - The shape of the range.
+	/// * 'radius' - How far the range reaches from the cell in the middle.
+	/// * 'middle' - True if the cell in the middle counts itself.
+	pub fn range(range: Range, radius: i32, middle: bool) -> Self {
+		let mut offsets = Vec::new();
+		for dy in -radius..radius + 1 {
+			for dx in -radius..radius + 1 {
+				let inside = range == Range::Moore || dx.abs() + dy.abs() <= radius;
+				if inside && (middle || (dx, dy) != (0, 0)) {
+					offsets.push((dx, dy, 1));
+				}
+			}
+		}
+		let mut name = match range {
+			Range::Moore => "moore".to_string(),
+			Range::VonNeumann => "vonneumann".to_string(),
+		};
+		if radius > 1 {
+			name += format!(" r{}", radius).as_str();
+		}
+		if middle {
+			name += " with middle";
+		}
+		Neighborhood { name, offsets, hex: false }
+	}
+
+	/// Looks up a built in shape by name.
+	///
+	/// # Arguments
+	/// * 'name' - "moore", "vonneumann", "hex" or "cross", in any case and with any spaces, - or _.
+	///
+	/// # Return
+	/// * Option<Neighborhood> - The shape, None if the name is unknown.
+	pub fn builtin(name: &str) -> Option<Neighborhood> {
+		let name: String = name.chars().filter(|c| !(c.is_whitespace() || *c == '-' || *c == '_')).collect();
+		match name.to_lowercase().as_str() {
+			"moore" => Some(Neighborhood::moore()),
+			"vonneumann" => Some(Neighborhood::von_neumann()),
+			"hex" | "hexagonal" => Some(Neighborhood::hexagonal()),
+			"cross" => Some(Neighborhood::cross()),
+			_ => None,
+		}
+	}
+
+	/// Parses a shape from the text format described at the top of this file.
+	///
+	/// # Arguments
+	/// * 'name' - The name to give the shape.
+	/// * 'text' - The grid, with any comments and a hex line.
+	///
+	/// # Return
+	/// * Result<Neighborhood, String> - The shape, or a description of what is wrong with the text.
+	pub fn parse(name: &str, text: &str) -> Result<Neighborhood, String> {
+		let mut hex = false;
+		let mut rows: Vec<Vec<char>> = Vec::new();
+		for line in text.lines() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			if line.eq_ignore_ascii_case("hex") && rows.is_empty() {
+				hex = true;
+				continue;
+			}
+			rows.push(line.chars().filter(|c| !c.is_whitespace()).collect());
+		}
+
+		let height = rows.len() as i32;
+		let width = rows.first().map_or(0, |row| row.len()) as i32;
+		if height % 2 == 0 || width % 2 == 0 {
+			return Err(format!("the grid is {}x{}, it needs an odd width and height to have a middle", width, height));
+		}
+		if rows.iter().any(|row| row.len() as i32 != width) {
+			return Err("every row of the grid must be the same width".to_string());
+		}
+
+		let mut offsets = Vec::new();
+		for (y, row) in rows.iter().enumerate() {
+			for (x, c) in row.iter().enumerate() {
+				match (*c, c.to_digit(10)) {
+					('.', _) => (),
+					(_, Some(weight)) if weight > 0 => offsets.push((x as i32 - width / 2, y as i32 - height / 2, weight as i32)),
+					_ => return Err(format!("'{}' is not a weight, expected . or 1 to 9", c)),
+				}
+			}
+		}
+		if offsets.is_empty() {
+			return Err("the grid has no neighbors".to_string());
+		}
+		Ok(Neighborhood { name: name.to_string(), offsets, hex })
+	}
+
+	/// Reads a shape from a file in the text format, named after the file.
+	///
+	/// # Arguments
+	/// * 'path' - The file.
+	///
+	/// # Return
+	/// * Result<Neighborhood, String> - The shape, or a description of why it couldn't be read.
+	pub fn load(path: &str) -> Result<Neighborhood, String> {
+		let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))?;
+		let name = Path::new(path).file_stem().and_then(|stem| stem.to_str()).unwrap_or(path);
+		Neighborhood::parse(name, text.as_str()).map_err(|error| format!("{}: {}", path, error))
+	}
+
+	/// Returns the name of the shape
+	pub fn name(&self) -> &str {
+		self.name.as_str()
+	}
+
+	/// Returns true if the shape is laid on a hex grid
+	pub fn is_hex(&self) -> bool {
+		self.hex
+	}
+
+	/// Returns the highest count possible, with every neighbor alive
+	pub fn most(&self) -> i32 {
+		self.offsets.iter().map(|&(_, _, weight)| weight).sum()
+	}
+
+	/// Counts the living neighbors around a target in a map of coordinates, by weight.
+	/// Neighbors off the edge of the map are dead.
+	///
+	/// # Arguments
+	/// * 'map' - The map of coordinates.
+	/// * 'x & y' - The target coordinate.
+	///
+	/// # Return
+	/// * i32 - The weighted number of living neighbors around the target.
+	pub fn count(&self, map: &[Vec<State>], x: i32, y: i32) -> i32 {
+		let mut count = 0;
+		self.each_living(map, x, y, |_, _, weight| count += weight);
+		count
+	}
+
+	/// Counts the living neighbors around a target of each color, by weight.
+	///
+	/// # Arguments
+	/// * 'map'    - The map of coordinates.
+	/// * 'colors' - The color of each cell in the map.
+	/// * 'x & y'  - The target coordinate.
+	///
+	/// # Return
+	/// * [i32; MAX_COLORS] - The weighted number of living neighbors of each color.
+	pub fn count_colors(&self, map: &[Vec<State>], colors: &[Vec<u8>], x: i32, y: i32) -> [i32; MAX_COLORS] {
+		let mut counts = [0; MAX_COLORS];
+		self.each_living(map, x, y, |x, y, weight| counts[colors[y][x] as usize] += weight);
+		counts
+	}
+
+	/// Visits each living neighbor around a target with its (x, y) and weight.
+	/// Neighbors off the edge of the map are dead.
+	fn each_living<F: FnMut(usize, usize, i32)>(&self, map: &[Vec<State>], x: i32, y: i32, mut visit: F) {
+		let width = map.first().map_or(0, |row| row.len()) as i32;
+		let height = map.len() as i32;
+		//On odd hex rows, the rows an odd number away are half a cell further right
+		let odd = self.hex && y & 1 == 1;
+		for &(dx, dy, weight) in self.offsets.iter() {
+			let x = x + dx + if odd && dy & 1 == 1 { 1 } else { 0 };
+			let y = y + dy;
+			if x >= 0 && y >= 0 && x < width && y < height && map[y as usize][x as usize] == ALIVE {
+				visit(x as usize, y as usize, weight);
+			}
+		}
+	}
+}
+
+#[test]
+fn test_neighborhood_builtins() {
+	use rule::{DEAD};
+
+	assert_eq!((8, 4, 6, 8), (Neighborhood::moore().most(), Neighborhood::von_neumann().most(), Neighborhood::hexagonal().most(), Neighborhood::cross().most()));
+	assert_eq!(Some(Neighborhood::von_neumann()), Neighborhood::builtin("Von Neumann"));
+	assert_eq!(Some(Neighborhood::hexagonal()), Neighborhood::builtin("HEX"));
+	assert_eq!(None, Neighborhood::builtin("knight"));
+	assert_eq!(Neighborhood::moore(), Neighborhood::range(Range::Moore, 1, false));
+	assert_eq!((25, "moore r2 with middle"), (Neighborhood::range(Range::Moore, 2, true).most(), Neighborhood::range(Range::Moore, 2, true).name()));
+
+	//A cell two away along the row is only counted by the cross
+	let mut map = vec![vec![DEAD; 5]; 5];
+	map[2][4] = ALIVE;
+	map[1][1] = ALIVE;
+	assert_eq!((1, 0, 1), (Neighborhood::moore().count(&map, 2, 2), Neighborhood::von_neumann().count(&map, 2, 2), Neighborhood::cross().count(&map, 2, 2)));
+
+	//Edges count as dead
+	let full = vec![vec![ALIVE; 5]; 5];
+	assert_eq!((3, 2, 4), (Neighborhood::moore().count(&full, 0, 0), Neighborhood::von_neumann().count(&full, 0, 0), Neighborhood::cross().count(&full, 0, 0)));
+	assert_eq!((5, 6), (Neighborhood::moore().count(&full, 2, 0), Neighborhood::cross().count(&full, 1, 1)));
+
+	//Living neighbors are counted by color too
+	let mut colors = vec![vec![0; 5]; 5];
+	colors[0][1] = 3;
+	colors[1][1] = 3;
+	assert_eq!([1, 0, 0, 2], Neighborhood::moore().count_colors(&full, &colors, 0, 0));
+	assert_eq!([0, 0, 0, 1], Neighborhood::von_neumann().count_colors(&map, &colors, 1, 2));
+}
+
+#[test]
+fn test_neighborhood_parse() {
+	use rule::{DEAD};
+
+	//A weighted shape, with the middle counting itself twice
+	let text = "# Weighted\n. 1 .\n1 2 1\n\n. 1 .\n";
+	let weighted = Neighborhood::parse("weighted", text).expect("Error parsing neighborhood");
+	assert_eq!(("weighted", 6, false), (weighted.name(), weighted.most(), weighted.is_hex()));
+	let mut map = vec![vec![DEAD; 3]; 3];
+	map[1][1] = ALIVE;
+	map[0][1] = ALIVE;
+	map[0][0] = ALIVE;
+	assert_eq!(3, weighted.count(&map, 1, 1));
+	assert_eq!(4, weighted.count(&map, 1, 0));
+
+	//Hex shapes shift the rows an odd number away on odd rows
+	let hex = Neighborhood::parse("hex", "hex\n11.\n1.1\n11.").expect("Error parsing neighborhood");
+	assert_eq!(Neighborhood::hexagonal(), hex);
+	let mut map = vec![vec![DEAD; 4]; 4];
+	map[0][2] = ALIVE;
+	assert_eq!((1, 0), (hex.count(&map, 1, 1), hex.count(&map, 1, 2)));
+	map[0][2] = DEAD;
+	map[0][0] = ALIVE;
+	assert_eq!((0, 0), (hex.count(&map, 1, 1), hex.count(&map, 1, 2)));
+	assert_eq!(1, hex.count(&map, 0, 1));
+
+	assert!(Neighborhood::parse("even", "1.\n.1").is_err());
+	assert!(Neighborhood::parse("ragged", "1.1\n.1\n1.1").is_err());
+	assert!(Neighborhood::parse("zero", "...\n.0.\n...").is_err());
+	assert!(Neighborhood::parse("empty", "...\n...\n...").is_err());
+	assert!(Neighborhood::parse("nothing", "# Just a comment").is_err());
+	assert!(Neighborhood::load("/no/such/shape.txt").is_err());
+}
@@ -14,48 +14,69 @@
 
 //Defines program wide parameters
 //for displaying in ggez
-//|-----------------------|-----|
-//|game                   |stats|
-//|                       |-----|
-//|                       |opts.|
-//|                       |-----|
-//|                       |Adv. |
-//|-----------------------|-----|
+//|-----------------------|-----|-----|
+//|game                   |stats|Adv. |
+//|                       |-----|-----|
+//|                       |opts.|pat- |
+//|                       |     |terns|
+//|                       |     |-----|
+//|                       |     |mini-|
+//|                       |     |map  |
+//|-----------------------|-----|-----|
 
 //Hight of a line of ui text
 pub const WIDTH_UI_INDENT:i32 = 5;
-pub const HEIGHT_UI_LINE:i32 = 7;
+pub const HEIGHT_UI_LINE:i32 = 6;
+
+//Size of ui text in points
+pub const SIZE_FONT:u32 = 20;
 
 //Size of grids in pixels
 pub const SIZE_GRID_PIXELS: i32 = 5;
 
 //program window
-pub const WIDTH_WINDOW_GRIDS:i32 = 150;
-pub const HEIGHT_WINDOW_GRIDS:i32 = 100;
+pub const WIDTH_WINDOW_GRIDS:i32 = 260;
+pub const HEIGHT_WINDOW_GRIDS:i32 = 180;
 pub const AREA_WINDOW_PIXELS: (i32, i32) = (WIDTH_WINDOW_GRIDS * SIZE_GRID_PIXELS, HEIGHT_WINDOW_GRIDS * SIZE_GRID_PIXELS);
 
+//columns of side panels
+pub const WIDTH_PANEL_GRIDS:i32 = 80;
+
 //game_frame
-pub const WIDTH_GAME_GRIDS:i32 = WIDTH_WINDOW_GRIDS - 50;
+pub const WIDTH_GAME_GRIDS:i32 = WIDTH_WINDOW_GRIDS - 2 * WIDTH_PANEL_GRIDS;
 pub const HEIGHT_GAME_GRIDS:i32 = HEIGHT_WINDOW_GRIDS;
 //const AREA_GAME_PIXELS: (i32, i32) = (WIDTH_GAME_GRIDS * SIZE_GRID_PIXELS, HEIGHT_GAME_GRIDS * SIZE_GRID_PIXELS);
 
+//largest world, in cells on a side
+pub const MAX_WORLD_GRIDS:i32 = 2000;
+
 //stat_frame
-pub const WIDTH_STAT_GRIDS:i32 = WIDTH_WINDOW_GRIDS - WIDTH_GAME_GRIDS;
-pub const HEIGHT_STAT_GRIDS:i32 = HEIGHT_WINDOW_GRIDS - 66;
+pub const WIDTH_STAT_GRIDS:i32 = WIDTH_PANEL_GRIDS;
+pub const HEIGHT_STAT_GRIDS:i32 = 16 * HEIGHT_UI_LINE;
 //const AREA_STAT_PIXELS: (i32, i32) = (WIDTH_STAT_GRIDS * SIZE_GRID_PIXELS, HEIGHT_STAT_GRIDS * SIZE_GRID_PIXELS);
 
+//option_frame
+pub const WIDTH_OPTION_GRIDS:i32 = WIDTH_PANEL_GRIDS;
+pub const HEIGHT_OPTION_GRIDS:i32 = HEIGHT_WINDOW_GRIDS - HEIGHT_STAT_GRIDS;
+//const AREA_OPTION_PIXELS: (i32, i32) = (WIDTH_OPTION_GRIDS * SIZE_GRID_PIXELS, HEIGHT_OPTION_GRIDS * SIZE_GRID_PIXELS);
+
 //advanced settings frame
-pub const WIDTH_ADVANCED_GRIDS:i32 = WIDTH_STAT_GRIDS;
-pub const HEIGHT_ADVANCED_GRIDS:i32 = HEIGHT_STAT_GRIDS;
+pub const WIDTH_ADVANCED_GRIDS:i32 = WIDTH_PANEL_GRIDS;
+pub const HEIGHT_ADVANCED_GRIDS:i32 = 11 * HEIGHT_UI_LINE;
 //const AREA_ADVANCED_PIXELS: (i32, i32) = (WIDTH_ADVANCED_GRIDS * SIZE_GRID_PIXELS, HEIGHT_ADVANCED_GRIDS * SIZE_GRID_PIXELS);
 
-//option_frame
-pub const WIDTH_OPTION_GRIDS:i32 = WIDTH_STAT_GRIDS;
-pub const HEIGHT_OPTION_GRIDS:i32 = HEIGHT_STAT_GRIDS;
-//const AREA_OPTION_PIXELS: (i32, i32) = (WIDTH_OPTION_GRIDS * SIZE_GRID_PIXELS, HEIGHT_OPTION_GRIDS * SIZE_GRID_PIXELS);
+//pattern_frame
+pub const WIDTH_PATTERN_GRIDS:i32 = WIDTH_PANEL_GRIDS;
+pub const HEIGHT_PATTERN_GRIDS:i32 = 7 * HEIGHT_UI_LINE;
+
+//minimap_frame
+pub const WIDTH_MINIMAP_GRIDS:i32 = WIDTH_PANEL_GRIDS;
+pub const HEIGHT_MINIMAP_GRIDS:i32 = HEIGHT_WINDOW_GRIDS - HEIGHT_ADVANCED_GRIDS - HEIGHT_PATTERN_GRIDS;
 
 //Set default coordinates of window elements
 pub const POS_GAME_GRIDS: (i32, i32) = (0,0);
 pub const POS_STAT_GRIDS: (i32, i32) = (POS_GAME_GRIDS.0 + (WIDTH_GAME_GRIDS), POS_GAME_GRIDS.1);
 pub const POS_OPTION_GRIDS: (i32, i32) = (POS_STAT_GRIDS.0, POS_STAT_GRIDS.1 + HEIGHT_STAT_GRIDS);
-pub const POS_ADVANCED_GRIDS: (i32, i32) = (POS_STAT_GRIDS.0, POS_OPTION_GRIDS.1 + HEIGHT_OPTION_GRIDS);
\ No newline at end of file
+pub const POS_ADVANCED_GRIDS: (i32, i32) = (POS_STAT_GRIDS.0 + WIDTH_STAT_GRIDS, POS_GAME_GRIDS.1);
+pub const POS_PATTERN_GRIDS: (i32, i32) = (POS_ADVANCED_GRIDS.0, POS_ADVANCED_GRIDS.1 + HEIGHT_ADVANCED_GRIDS);
+pub const POS_MINIMAP_GRIDS: (i32, i32) = (POS_PATTERN_GRIDS.0, POS_PATTERN_GRIDS.1 + HEIGHT_PATTERN_GRIDS);
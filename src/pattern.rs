@@ -0,0 +1,476 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Patterns are blocks of cells that can be stamped onto the map. They are stored
+// in run length encoded (RLE) files, the format most Life software shares:
+//
+//     #N Glider
+//     x = 3, y = 3, rule = B3/S23
+//     bo$2bo$3o!
+//
+// 'b' is a dead cell, 'o' a living one, '$' ends a row and '!' ends the pattern.
+// A number in front of any of them repeats it. Patterns with more than two states,
+// such as WireWorld circuits, write '.' for a dead cell and 'A', 'B', 'C' and on for
+// states 1, 2, 3 and on.
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use camera::{Camera};
+use params::{MAX_WORLD_GRIDS};
+use render::{Color, Renderer};
+use rule::{State, DEAD, ALIVE};
+
+///Longest line written to RLE files
+const MAX_RLE_LINE: usize = 70;
+///Highest state with a single letter tag in RLE files, 'X'
+const MAX_RLE_STATE: State = 24;
+
+///A named block of cells
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+	name: String,
+	width: i32,
+	height: i32,
+	///Cells that aren't dead, relative to the top left corner of the block
+	cells: Vec<(i32, i32)>,
+	///State of each of the cells, ALIVE in two state patterns
+	states: Vec<State>,
+	///Rule the pattern was made under, from the header of its file
+	rule: Option<String>,
+}
+
+impl Pattern {
+	/// Used only in testing.
+	/// Generates a pattern from its living cells.
+	///
+	/// # Arguments
+	/// * 'name'  - The name of the pattern.
+	/// * 'size'  - The (width, height) of the block.
+	/// * 'cells' - The living cells, relative to the top left corner of the block.
+	#[allow(dead_code)]
+	pub fn new(name: &str, size: (i32, i32), cells: Vec<(i32, i32)>) -> Self {
+		let states = vec![ALIVE; cells.len()];
+		Pattern { name: name.to_string(), width: size.0, height: size.1, cells, states, rule: None }
+	}
+
+	/// Generates a pattern from cells in any state.
+	///
+	/// # Arguments
+	/// * 'name'  - The name of the pattern.
+	/// * 'size'  - The (width, height) of the block.
+	/// * 'cells' - The cells that aren't dead as (x, y, state), relative to the top left corner of the block.
+	/// * 'rule'  - The rule the pattern was made under, if known.
+	pub fn with_states(name: &str, size: (i32, i32), cells: Vec<(i32, i32, State)>, rule: Option<String>) -> Self {
+		Pattern {
+			name: name.to_string(),
+			width: size.0,
+			height: size.1,
+			states: cells.iter().map(|&(_, _, state)| state).collect(),
+			cells: cells.into_iter().map(|(x, y, _)| (x, y)).collect(),
+			rule,
+		}
+	}
+
+	/// Parses a pattern from RLE text.
+	///
+	/// # Arguments
+	/// * 'name' - The name to use if the text doesn't have a "#N" line.
+	/// * 'text' - The RLE text.
+	///
+	/// # Return
+	/// * Result<Pattern, String> - The pattern, or a description of what is wrong with the text.
+	pub fn parse_rle(name: &str, text: &str) -> Result<Pattern, String> {
+		let mut name = name.to_string();
+		let mut size = (0, 0);
+		let mut rule = None;
+		let mut body = String::new();
+
+		for line in text.lines() {
+			let line = line.trim();
+			if let Some(rest) = line.strip_prefix("#N") {
+				name = rest.trim().to_string();
+			}
+			else if line.starts_with('#') {
+				continue;
+			}
+			//The header comes before the body, as "x = 3, y = 3, rule = B3/S23"
+			else if line.starts_with('x') && body.is_empty() {
+				for field in line.split(',') {
+					let mut parts = field.split('=').map(|part| part.trim());
+					let (key, value) = (parts.next(), parts.next());
+					match (key, value.map(|value| value.parse::<i32>())) {
+						(Some("x"), Some(Ok(width))) if (0..=MAX_WORLD_GRIDS).contains(&width) => size.0 = width,
+						(Some("y"), Some(Ok(height))) if (0..=MAX_WORLD_GRIDS).contains(&height) => size.1 = height,
+						(Some("x"), _) | (Some("y"), _) => return Err(format!("bad header '{}'", line)),
+						(Some("rule"), _) => rule = value.map(|value| value.to_string()),
+						_ => (),
+					}
+				}
+			}
+			else {
+				body.push_str(line);
+			}
+		}
+
+		//Runs have to fit in the size given by the header, or in the largest world without one
+		let limit = |size: i32| if size > 0 { size } else { MAX_WORLD_GRIDS };
+		let (width, height) = (limit(size.0), limit(size.1));
+		let too_long = || format!("run doesn't fit in {}x{}", width, height);
+
+		let mut cells = Vec::new();
+		let (mut x, mut y) = (0, 0);
+		let mut run: i32 = 0;
+		for c in body.chars() {
+			let count = run.max(1);
+			match c {
+				'0'..='9' => {
+					let digit = c.to_digit(10).expect("Error reading digit") as i32;
+					run = run.checked_mul(10).and_then(|run| run.checked_add(digit)).filter(|run| *run <= width.max(height))
+						.ok_or_else(too_long)?;
+					continue;
+				},
+				//Rows past the last are only refused once a cell lands on them, so a trailing '$' is fine
+				'b' | '.' | 'o' | 'A'..='X' if x + count > width || y >= height => return Err(too_long()),
+				'b' | '.' => x += count,
+				'o' | 'A'..='X' => {
+					let state = if c == 'o' { ALIVE } else { (c as u8 - b'A') as State + 1 };
+					cells.extend((0..count).map(|i| (x + i, y, state)));
+					x += count;
+				},
+				'$' => {
+					y = y.saturating_add(count);
+					x = 0;
+				},
+				'!' => break,
+				c if c.is_whitespace() => continue,
+				c => return Err(format!("unexpected '{}' in pattern", c)),
+			}
+			run = 0;
+		}
+
+		//Patterns without a header are as big as their cells
+		if size.0 == 0 || size.1 == 0 {
+			for &(x, y, _) in cells.iter() {
+				size = (size.0.max(x + 1), size.1.max(y + 1));
+			}
+		}
+		Ok(Pattern::with_states(name.as_str(), size, cells, rule))
+	}
+
+	/// Writes the pattern as RLE text, with letters for the states if it has more than two.
+	pub fn to_rle(&self) -> String {
+		let mut rows = vec![vec![DEAD; self.width as usize]; self.height as usize];
+		for (&(x, y), &state) in self.cells.iter().zip(self.states.iter()) {
+			rows[y as usize][x as usize] = state.min(MAX_RLE_STATE);
+		}
+		let lettered = self.states.iter().any(|state| *state != ALIVE);
+		let tag = |state: State| match (state, lettered) {
+			(DEAD, false) => 'b',
+			(_, false) => 'o',
+			(DEAD, true) => '.',
+			(state, true) => (b'A' + state - 1) as char,
+		};
+
+		//Runs of one state, as (count, tag). Dead cells at the end of a row are left off.
+		let mut runs: Vec<(usize, char)> = Vec::new();
+		let push = |runs: &mut Vec<(usize, char)>, tag: char| {
+			if let Some(last) = runs.last_mut() {
+				if last.1 == tag {
+					last.0 += 1;
+					return;
+				}
+			}
+			runs.push((1, tag));
+		};
+		for (y, row) in rows.iter().enumerate() {
+			if y > 0 {
+				push(&mut runs, '$');
+			}
+			let end = row.iter().rposition(|state| *state != DEAD).map_or(0, |x| x + 1);
+			for state in row[..end].iter() {
+				push(&mut runs, tag(*state));
+			}
+		}
+		//Rows left empty at the bottom don't need ending
+		while runs.last().is_some_and(|run| run.1 == '$') {
+			runs.pop();
+		}
+
+		let mut text = format!("#N {}\nx = {}, y = {}", self.name, self.width, self.height);
+		if let Some(ref rule) = self.rule {
+			text += format!(", rule = {}", rule).as_str();
+		}
+		text.push('\n');
+		let mut line = String::new();
+		for (count, tag) in runs.into_iter().chain(Some((1, '!'))) {
+			let run = if count > 1 { format!("{}{}", count, tag) } else { tag.to_string() };
+			if line.len() + run.len() > MAX_RLE_LINE {
+				text.push_str(line.as_str());
+				text.push('\n');
+				line.clear();
+			}
+			line.push_str(run.as_str());
+		}
+		text.push_str(line.as_str());
+		text.push('\n');
+		text
+	}
+
+	/// Writes the pattern to an RLE file, replacing any file already there.
+	///
+	/// # Arguments
+	/// * 'path' - The file to write.
+	///
+	/// # Return
+	/// * Result<(), String> - A description of what went wrong, if anything.
+	pub fn save(&self, path: &Path) -> Result<(), String> {
+		File::create(path)
+			.and_then(|mut file| file.write_all(self.to_rle().as_bytes()))
+			.map_err(|error| format!("{}: {}", path.display(), error))
+	}
+
+	/// Loads every .rle file in a directory, sorted by name. A bad file doesn't stop the others loading.
+	///
+	/// # Arguments
+	/// * 'dir' - The directory to read.
+	///
+	/// # Return
+	/// * (Vec<Pattern>, Vec<String>) - The patterns, and a description of each file that couldn't be loaded.
+	pub fn load_dir(dir: &Path) -> (Vec<Pattern>, Vec<String>) {
+		let mut patterns = Vec::new();
+		let mut errors = Vec::new();
+
+		let entries = match fs::read_dir(dir) {
+			Ok(entries) => entries,
+			Err(error) => return (patterns, vec![format!("{}: {}", dir.display(), error)]),
+		};
+		for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+			if path.extension().and_then(|extension| extension.to_str()) != Some("rle") {
+				continue;
+			}
+			let name = path.file_stem().map_or(String::new(), |stem| stem.to_string_lossy().to_string());
+			let mut text = String::new();
+			let result = File::open(&path)
+				.and_then(|mut file| file.read_to_string(&mut text))
+				.map_err(|error| error.to_string())
+				.and_then(|_| Pattern::parse_rle(name.as_str(), text.as_str()));
+			match result {
+				Ok(pattern) => patterns.push(pattern),
+				Err(error) => errors.push(format!("{}: {}", path.display(), error)),
+			}
+		}
+
+		patterns.sort_by_key(|pattern| pattern.name.to_lowercase());
+		(patterns, errors)
+	}
+
+	/// Returns the name of the pattern
+	pub fn name(&self) -> &str {
+		self.name.as_str()
+	}
+
+	/// Returns the (width, height) of the block
+	pub fn size(&self) -> (i32, i32) {
+		(self.width, self.height)
+	}
+
+	/// Renames the pattern
+	pub fn rename(&mut self, name: &str) {
+		self.name = name.to_string();
+	}
+
+	/// Returns the cells that aren't dead, relative to the top left corner of the block
+	pub fn cells(&self) -> &Vec<(i32, i32)> {
+		&self.cells
+	}
+
+	/// Returns the state of each of the cells, in the same order
+	pub fn states(&self) -> &Vec<State> {
+		&self.states
+	}
+
+	/// Returns the rule the pattern was made under, if its file named one
+	pub fn rule(&self) -> Option<&str> {
+		self.rule.as_deref()
+	}
+
+	/// Turns the pattern a quarter turn clockwise
+	pub fn rotate(&mut self) {
+		let height = self.height;
+		for cell in self.cells.iter_mut() {
+			*cell = (height - 1 - cell.1, cell.0);
+		}
+		self.height = self.width;
+		self.width = height;
+	}
+
+	/// Mirrors the pattern left to right
+	pub fn flip_horizontal(&mut self) {
+		let width = self.width;
+		for cell in self.cells.iter_mut() {
+			cell.0 = width - 1 - cell.0;
+		}
+	}
+
+	/// Mirrors the pattern top to bottom
+	pub fn flip_vertical(&mut self) {
+		let height = self.height;
+		for cell in self.cells.iter_mut() {
+			cell.1 = height - 1 - cell.1;
+		}
+	}
+
+	/// Draws the cells of the pattern, such as to preview where it will land.
+	///
+	/// # Arguments
+	/// * 'renderer' - The backend to draw with.
+	/// * 'camera'   - Decides which cells are drawn where.
+	/// * 'at'       - The cell under the top left corner of the block.
+	/// * 'color'    - The color of the cells.
+	/// * 'hex'      - True if the world is a hex grid, where odd rows sit half a cell right of even rows.
+	pub fn draw(&self, renderer: &mut dyn Renderer, camera: &Camera, at: (i32, i32), color: Color, hex: bool) {
+		//Keep tiny cells visible when zoomed far out
+		let size = camera.zoom().max(1.0);
+		renderer.set_color(color);
+		for &(x, y) in self.cells.iter() {
+			let (x, y) = (at.0 + x, at.1 + y);
+			let shift = if hex && y & 1 == 1 { 0.5 } else { 0.0 };
+			let (px, py) = camera.to_screen(x as f32 + shift, y as f32);
+			if let Some((px, py, w, h)) = camera.clip((px, py, size, size)) {
+				renderer.fill_rect(px, py, w, h);
+			}
+		}
+	}
+}
+
+#[test]
+fn test_pattern_parse_rle() {
+	let glider = Pattern::parse_rle("glider", "#C A comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+	assert_eq!("glider", glider.name());
+	assert_eq!((3, 3), glider.size());
+	assert_eq!(&vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)], glider.cells());
+
+	//Runs of row ends skip blank rows, bodies may span lines, and a #N line names the pattern
+	let gap = Pattern::parse_rle("file", "#N Gap\nx = 2, y = 4\no\nb$\n2$bo!").unwrap();
+	assert_eq!("Gap", gap.name());
+	assert_eq!((2, 4), gap.size());
+	assert_eq!(&vec![(0, 0), (1, 3)], gap.cells());
+
+	//Without a header the size comes from the cells
+	assert_eq!((12, 1), Pattern::parse_rle("line", "12o!").unwrap().size());
+
+	assert_eq!(Err("unexpected 'z' in pattern".to_string()), Pattern::parse_rle("bad", "3oz!"));
+	assert_eq!(Err("bad header 'x = three'".to_string()), Pattern::parse_rle("bad", "x = three\n3o!"));
+	assert_eq!(Err("bad header 'x = -3, y = 1'".to_string()), Pattern::parse_rle("bad", "x = -3, y = 1\n3o!"));
+
+	//Runs too long for the header, or for the largest world without one, are refused
+	let too_long = |size: (i32, i32)| Err(format!("run doesn't fit in {}x{}", size.0, size.1));
+	assert_eq!(too_long((3, 3)), Pattern::parse_rle("bad", "x = 3, y = 3\nb3o!"));
+	assert_eq!(too_long((3, 3)), Pattern::parse_rle("bad", "x = 3, y = 3\n4$o!"));
+	assert_eq!(too_long((3, 3)), Pattern::parse_rle("bad", "x = 3, y = 3\n99999999999999999999o!"));
+	assert_eq!(too_long((MAX_WORLD_GRIDS, MAX_WORLD_GRIDS)), Pattern::parse_rle("bad", "2147483647o!"));
+
+	//Cells on a row past the header's height are refused too, though a trailing '$' is not
+	assert_eq!(too_long((1, 1)), Pattern::parse_rle("bad", "x = 1, y = 1\no$o!"));
+	assert_eq!(Ok((1, 1)), Pattern::parse_rle("tall", "x = 1, y = 1\no$!").map(|pattern| (pattern.width, pattern.height)));
+
+	//Writing and reading back gives the same pattern
+	let rle = gap.to_rle();
+	assert_eq!("#N Gap\nx = 2, y = 4\no3$bo!\n", rle);
+	assert_eq!(Ok(gap), Pattern::parse_rle("copy", rle.as_str()));
+	let long = Pattern::new("Long", (200, 1), (0..200).filter(|x| x % 2 == 0).map(|x| (x, 0)).collect());
+	assert!(long.to_rle().lines().all(|line| line.len() <= MAX_RLE_LINE));
+	assert_eq!(Ok(long.clone()), Pattern::parse_rle("copy", long.to_rle().as_str()));
+}
+
+#[test]
+fn test_pattern_states() {
+	//A WireWorld clock, an electron running around a loop of wire
+	let clock = Pattern::parse_rle("clock", "x = 6, y = 3, rule = WireWorld\n.BA2C$C4.C$.4C!").unwrap();
+	assert_eq!(((6, 3), Some("WireWorld")), (clock.size(), clock.rule()));
+	assert_eq!(&vec![(1, 0), (2, 0), (3, 0), (4, 0), (0, 1), (5, 1), (1, 2), (2, 2), (3, 2), (4, 2)], clock.cells());
+	assert_eq!(&vec![2, 1, 3, 3, 3, 3, 3, 3, 3, 3], clock.states());
+
+	//States are written back as letters, along with the rule
+	let mut copy = clock.clone();
+	copy.rename("Clock");
+	assert_eq!("#N Clock\nx = 6, y = 3, rule = WireWorld\n.BA2C$C4.C$.4C!\n", copy.to_rle());
+	assert_eq!(Ok(copy.clone()), Pattern::parse_rle("copy", copy.to_rle().as_str()));
+
+	//Turning keeps each cell's state with it
+	copy.rotate();
+	assert_eq!(clock.states(), copy.states());
+	assert_eq!((2, 1), copy.cells()[0]);
+}
+
+#[test]
+fn test_pattern_transform() {
+	let mut pattern = Pattern::new("L", (2, 3), vec![(0, 0), (0, 1), (0, 2), (1, 2)]);
+	let original = pattern.clone();
+
+	//L on its back, with the foot hanging down on the left
+	pattern.rotate();
+	assert_eq!((3, 2), pattern.size());
+	assert_eq!(&vec![(2, 0), (1, 0), (0, 0), (0, 1)], pattern.cells());
+
+	//Four turns come back around
+	pattern.rotate();
+	pattern.rotate();
+	pattern.rotate();
+	assert_eq!(original, pattern);
+
+	pattern.flip_horizontal();
+	assert_eq!(&vec![(1, 0), (1, 1), (1, 2), (0, 2)], pattern.cells());
+	pattern.flip_vertical();
+	assert_eq!(&vec![(1, 2), (1, 1), (1, 0), (0, 0)], pattern.cells());
+}
+
+#[test]
+fn test_pattern_draw() {
+	use render::{MemoryRenderer};
+	let camera = Camera::new((0.0, 0.0, 100.0, 100.0));
+	let zoom = camera.zoom();
+	let column = Pattern::new("Column", (1, 2), vec![(0, 0), (0, 1)]);
+	let lefts = |hex: bool| {
+		let mut renderer = MemoryRenderer::new();
+		column.draw(&mut renderer, &camera, (1, 2), [1.0, 1.0, 1.0, 1.0], hex);
+		renderer.rects().iter().map(|rect| (rect.1).0).collect::<Vec<f32>>()
+	};
+
+	//On a hex grid the cell on the odd row sits half a cell right, as the world draws it
+	assert_eq!(vec![zoom, zoom], lefts(false));
+	assert_eq!(vec![zoom, zoom * 1.5], lefts(true));
+}
+
+#[test]
+fn test_pattern_library() {
+	//The bundled patterns sit in the crate's directory
+	let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("patterns");
+	let (patterns, errors) = Pattern::load_dir(&dir);
+	assert_eq!(Vec::<String>::new(), errors);
+
+	let population = |name: &str| {
+		patterns.iter().find(|pattern| pattern.name() == name).map(|pattern| pattern.cells().len())
+	};
+	assert_eq!(Some(5), population("Glider"));
+	assert_eq!(Some(9), population("Lightweight spaceship"));
+	assert_eq!(Some(36), population("Gosper glider gun"));
+	assert_eq!(Some(48), population("Pulsar"));
+	assert_eq!(Some(7), population("Acorn"));
+
+	//Sorted by name
+	assert_eq!("Acorn", patterns[0].name());
+}
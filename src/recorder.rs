@@ -0,0 +1,213 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Stats recording. A Recorder streams one record of Stats per generation to a
+// file or stdout, as CSV with a header row or as JSON Lines with one object per line.
+// Output is buffered, and flushed when the recorder is dropped.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use gol::{Stats};
+
+///Written in place of a file name to record to stdout
+pub const STDOUT: &str = "-";
+
+///Layout of the records
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+	///Comma separated values, with a header row
+	Csv,
+	///One JSON object per line
+	Jsonl,
+}
+
+impl Format {
+	/// Looks up a format by name.
+	///
+	/// # Arguments
+	/// * 'name' - "csv" or "jsonl", in any case.
+	///
+	/// # Return
+	/// * Option<Format> - The format, None if the name is unknown.
+	pub fn from_name(name: &str) -> Option<Format> {
+		match name.to_lowercase().as_str() {
+			"csv" => Some(Format::Csv),
+			"jsonl" => Some(Format::Jsonl),
+			_ => None,
+		}
+	}
+
+	/// Picks a format from the extension of a file name. Anything but .jsonl is CSV.
+	///
+	/// # Arguments
+	/// * 'target' - The file name.
+	pub fn from_target(target: &str) -> Format {
+		if target.to_lowercase().ends_with(".jsonl") { Format::Jsonl } else { Format::Csv }
+	}
+
+	/// Returns the line written before any records, None if the format has none
+	pub fn header(&self) -> Option<&'static str> {
+		match *self {
+			Format::Csv => Some("generation,living,births,deaths,changed,total_births,total_deaths,peak,peak_generation,x,y,width,height,density"),
+			Format::Jsonl => None,
+		}
+	}
+
+	/// Writes the stats of one generation as a line, without the line ending.
+	///
+	/// # Arguments
+	/// * 'stats' - The stats to write.
+	pub fn record(&self, stats: &Stats) -> String {
+		match *self {
+			Format::Csv => {
+				//The bounds are left empty when nothing is alive
+				let bounds = match stats.bounds {
+					Some((x, y, width, height)) => format!("{},{},{},{}", x, y, width, height),
+					None => ",,,".to_string(),
+				};
+				format!("{},{},{},{},{},{},{},{},{},{},{}",
+					stats.generation, stats.living, stats.births, stats.deaths, stats.changed,
+					stats.total_births, stats.total_deaths, stats.peak, stats.peak_generation,
+					bounds, stats.density)
+			},
+			Format::Jsonl => {
+				let bounds = match stats.bounds {
+					Some((x, y, width, height)) => format!("[{},{},{},{}]", x, y, width, height),
+					None => "null".to_string(),
+				};
+				format!("{{\"generation\":{},\"living\":{},\"births\":{},\"deaths\":{},\"changed\":{},\"total_births\":{},\"total_deaths\":{},\"peak\":{},\"peak_generation\":{},\"bounds\":{},\"density\":{}}}",
+					stats.generation, stats.living, stats.births, stats.deaths, stats.changed,
+					stats.total_births, stats.total_deaths, stats.peak, stats.peak_generation,
+					bounds, stats.density)
+			},
+		}
+	}
+}
+
+///Streams stats to a file or stdout
+pub struct Recorder {
+	format: Format,
+	///Where records are written, buffered
+	out: Box<dyn Write>,
+	///Describes where records are written, for messages
+	target: String,
+}
+
+impl Recorder {
+	/// Generates a recorder writing to a file, or to stdout, and writes the format's header.
+	///
+	/// # Arguments
+	/// * 'target' - The file to create or replace, or "-" for stdout.
+	/// * 'format' - The layout of the records. None picks one from the file name.
+	///
+	/// # Return
+	/// * Result<Recorder, String> - The recorder, or a description of why the file couldn't be made.
+	pub fn open(target: &str, format: Option<Format>) -> Result<Recorder, String> {
+		let format = format.unwrap_or_else(|| Format::from_target(target));
+		let out: Box<dyn Write> = if target == STDOUT {
+			Box::new(BufWriter::new(io::stdout()))
+		}
+		else {
+			let file = File::create(target).map_err(|error| format!("{}: {}", target, error))?;
+			Box::new(BufWriter::new(file))
+		};
+
+		let mut recorder = Recorder { format, out, target: target.to_string() };
+		if let Some(header) = format.header() {
+			writeln!(recorder.out, "{}", header).map_err(|error| format!("{}: {}", target, error))?;
+		}
+		Ok(recorder)
+	}
+
+	/// Returns the file being written, or "-" for stdout
+	pub fn target(&self) -> &str {
+		self.target.as_str()
+	}
+
+	/// Writes the stats of one generation.
+	///
+	/// # Arguments
+	/// * 'stats' - The stats to write.
+	///
+	/// # Return
+	/// * Result<(), String> - A description of what went wrong, if anything.
+	pub fn write(&mut self, stats: &Stats) -> Result<(), String> {
+		let line = self.format.record(stats);
+		writeln!(self.out, "{}", line).map_err(|error| format!("{}: {}", self.target, error))
+	}
+}
+
+impl Drop for Recorder {
+	/// Writes out anything still buffered
+	fn drop(&mut self) {
+		if let Err(error) = self.out.flush() {
+			eprintln!("Error saving stats: {}: {}", self.target, error);
+		}
+	}
+}
+
+#[test]
+fn test_recorder_formats() {
+	use gol::{World};
+
+	let mut world = World::generate(0, (4, 4), None);
+	world.set_line((0, 1), (2, 1), true);
+	let stats = world.update();
+
+	assert_eq!(Some(Format::Jsonl), Format::from_name("JSONL"));
+	assert_eq!(None, Format::from_name("xml"));
+	assert_eq!(Format::Jsonl, Format::from_target("runs/soup.jsonl"));
+	assert_eq!(Format::Csv, Format::from_target(STDOUT));
+
+	//One column per header field
+	let header = Format::Csv.header().unwrap();
+	let record = Format::Csv.record(&stats);
+	assert_eq!(header.split(',').count(), record.split(',').count());
+	assert_eq!("1,3,2,2,4,2,2,3,0,1,0,1,3,0.1875", record);
+	assert_eq!(
+		"{\"generation\":1,\"living\":3,\"births\":2,\"deaths\":2,\"changed\":4,\"total_births\":2,\"total_deaths\":2,\"peak\":3,\"peak_generation\":0,\"bounds\":[1,0,1,3],\"density\":0.1875}",
+		Format::Jsonl.record(&stats)
+	);
+
+	//Nothing alive leaves the bounds empty
+	world.fill((0, 0, 4, 4), false);
+	let stats = world.stats();
+	assert!(Format::Csv.record(&stats).contains(",,,,"));
+	assert!(Format::Jsonl.record(&stats).contains("\"bounds\":null"));
+	assert_eq!(None, Format::Jsonl.header());
+}
+
+#[test]
+fn test_recorder_writes_file() {
+	use std::env;
+	use std::fs;
+	use gol::{World};
+
+	let path = env::temp_dir().join(format!("game_of_life_stats_{}.jsonl", std::process::id()));
+	let target = path.to_str().expect("Error naming stats file").to_string();
+	let mut world = World::generate(0, (4, 4), None);
+	{
+		let mut recorder = Recorder::open(target.as_str(), None).expect("Error opening stats file");
+		recorder.write(&world.stats()).expect("Error writing stats");
+		recorder.write(&world.update()).expect("Error writing stats");
+	}
+
+	//Dropping the recorder flushed both records
+	let text = fs::read_to_string(&path).expect("Error reading stats file");
+	fs::remove_file(&path).expect("Error removing stats file");
+	assert_eq!(2, text.lines().count());
+	assert!(text.lines().nth(1).unwrap().starts_with("{\"generation\":1,"));
+	assert!(Recorder::open("/no/such/dir/stats.csv", None).is_err());
+}
@@ -0,0 +1,372 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Drawing backends. The game and UI only ever talk to the Renderer trait, so the
+// same draw code can paint a ggez window or be recorded in memory for testing.
+
+//Import graphics crate
+extern crate ggez;
+use ggez::{Context, graphics};
+use ggez::graphics::{Point2};
+
+//Import global parameters
+use params::{ SIZE_FONT };
+
+///Color as [r, g, b, a], each between 0.0 and 1.0
+pub type Color = [f32; 4];
+
+///Colors picked by a number, such as the age of a cell, blending between colors set at a few numbers
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+	///(number, color) pairs in order of number
+	stops: Vec<(u32, Color)>,
+}
+
+impl Gradient {
+	/// Generates a gradient. Numbers before the first stop get its color, and numbers after the last get its color.
+	///
+	/// # Arguments
+	/// * 'stops' - The colors to blend between as (number, color) pairs, in any order. There must be at least one.
+	pub fn new(mut stops: Vec<(u32, Color)>) -> Self {
+		assert!(!stops.is_empty(), "Error generating gradient: no colors");
+		stops.sort_by_key(|stop| stop.0);
+		Gradient { stops }
+	}
+
+	/// Reads a gradient from comma separated stops, each a number and a hex color such as
+	/// "1:f2991a, 100:663399". Colors are RRGGBB, or RRGGBBAA to set how solid they are.
+	///
+	/// # Arguments
+	/// * 'text' - The stops.
+	///
+	/// # Return
+	/// * Result<Gradient, String> - The gradient, or a description of the stop that couldn't be read.
+	pub fn parse(text: &str) -> Result<Gradient, String> {
+		let mut stops = Vec::new();
+		for stop in text.split(',').map(|stop| stop.trim()).filter(|stop| !stop.is_empty()) {
+			let error = || format!("Stops are number:RRGGBB, not {}", stop);
+			let mut parts = stop.splitn(2, ':');
+			let number = parts.next().and_then(|number| number.trim().parse::<u32>().ok()).ok_or_else(error)?;
+			let hex = parts.next().map(|hex| hex.trim().trim_start_matches('#')).ok_or_else(error)?;
+			if (hex.len() != 6 && hex.len() != 8) || !hex.is_ascii() {
+				return Err(error());
+			}
+
+			//Colors without alpha are solid
+			let mut color = [1.0; 4];
+			for (i, part) in color.iter_mut().enumerate().take(hex.len() / 2) {
+				*part = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| error())? as f32 / 255.0;
+			}
+			stops.push((number, color));
+		}
+		if stops.is_empty() {
+			return Err("No colors given".to_string());
+		}
+		Ok(Gradient::new(stops))
+	}
+
+	/// Picks the color for a number, blending the stops either side of it.
+	///
+	/// # Arguments
+	/// * 'number' - The number to color.
+	///
+	/// # Return
+	/// * Color - The blended color.
+	pub fn color(&self, number: u32) -> Color {
+		let after = self.stops.iter().position(|stop| stop.0 > number).unwrap_or(self.stops.len());
+		if after == 0 {
+			return self.stops[0].1;
+		}
+		if after == self.stops.len() {
+			return self.stops[after - 1].1;
+		}
+
+		let (from, from_color) = self.stops[after - 1];
+		let (to, to_color) = self.stops[after];
+		let t = (number - from) as f32 / (to - from) as f32;
+		let mut color = from_color;
+		for (part, to_part) in color.iter_mut().zip(to_color.iter()) {
+			*part += (to_part - *part) * t;
+		}
+		color
+	}
+}
+
+///Width of a character and height of a line of text for renderers without a font
+const SIZE_MEMORY_CHAR: (f32, f32) = (10.0, 24.0);
+
+///Drawing primitives used by the game and UI. All positions are in window pixels.
+pub trait Renderer {
+	/// Sets the color used by every following draw call.
+	///
+	/// # Arguments
+	/// * 'color' - The new color.
+	fn set_color(&mut self, color: Color);
+
+	/// Draws a filled rectangle.
+	///
+	/// # Arguments
+	/// * 'x & y' - The top left corner of the rectangle.
+	/// * 'w & h' - The width and height of the rectangle.
+	fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32);
+
+	/// Draws a line between two points.
+	///
+	/// # Arguments
+	/// * 'from & to' - The end points of the line.
+	/// * 'width'     - The thickness of the line.
+	fn line(&mut self, from: (f32, f32), to: (f32, f32), width: f32);
+
+	/// Draws a filled convex polygon.
+	///
+	/// # Arguments
+	/// * 'points' - The corners of the polygon in order.
+	fn fill_polygon(&mut self, points: &[(f32, f32)]);
+
+	/// Draws a block of pixels, each with its own color. The current color is ignored.
+	///
+	/// # Arguments
+	/// * 'x & y'          - The top left corner of the block.
+	/// * 'width & height' - The size of the block in pixels.
+	/// * 'rgba'           - Four bytes of color per pixel, row by row.
+	/// * 'scale'          - Window pixels drawn for each pixel of the block, on a side.
+	fn pixels(&mut self, x: f32, y: f32, width: u16, height: u16, rgba: &[u8], scale: f32);
+
+	/// Draws a string.
+	///
+	/// # Arguments
+	/// * 'text'  - The string to draw.
+	/// * 'x & y' - The top left corner of the text.
+	///
+	/// # Return
+	/// * (f32, f32) - The width and height of the drawn text.
+	fn text(&mut self, text: &str, x: f32, y: f32) -> (f32, f32);
+
+	/// Measures a string without drawing it.
+	///
+	/// # Arguments
+	/// * 'text' - The string to measure.
+	///
+	/// # Return
+	/// * (f32, f32) - The width and height the text would take up.
+	fn text_size(&mut self, text: &str) -> (f32, f32);
+}
+
+///Renderer that draws to a ggez window
+pub struct GgezRenderer<'a> {
+	ctx: &'a mut Context,
+	font: graphics::Font,
+}
+
+impl<'a> GgezRenderer<'a> {
+	/// Generates a renderer for one frame.
+	///
+	/// # Arguments
+	/// * 'ctx' - [ggez global resources](https://docs.rs/ggez/0.3.1/ggez/struct.Context.html).
+	pub fn new(ctx: &'a mut Context) -> Self {
+		let font = graphics::Font::new(ctx, "/Pacifico.ttf", SIZE_FONT).expect("Missing ttf file");
+		GgezRenderer { ctx, font, }
+	}
+}
+
+impl<'a> Renderer for GgezRenderer<'a> {
+	fn set_color(&mut self, color: Color) {
+		graphics::set_color(self.ctx, color.into()).expect("Error setting color");
+	}
+
+	fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32) {
+		graphics::rectangle(self.ctx,
+			graphics::DrawMode::Fill,
+			graphics::Rect::new(x, y, w, h)
+		).expect("Error drawing Rect");
+	}
+
+	fn line(&mut self, from: (f32, f32), to: (f32, f32), width: f32) {
+		graphics::line(
+			self.ctx,
+			&[Point2::new(from.0, from.1), Point2::new(to.0, to.1)],
+			width
+		).expect("Error generating line");
+	}
+
+	fn fill_polygon(&mut self, points: &[(f32, f32)]) {
+		let points: Vec<Point2> = points.iter().map(|&(x, y)| Point2::new(x, y)).collect();
+		graphics::polygon(self.ctx, graphics::DrawMode::Fill, &points).expect("Error drawing polygon");
+	}
+
+	fn pixels(&mut self, x: f32, y: f32, width: u16, height: u16, rgba: &[u8], scale: f32) {
+		let mut image = graphics::Image::from_rgba8(self.ctx, width, height, rgba).expect("Error generating image");
+		//Keep pixels sharp when scaled up
+		image.set_filter(graphics::FilterMode::Nearest);
+		graphics::draw_ex(self.ctx, &image, graphics::DrawParam {
+			dest: Point2::new(x, y),
+			scale: Point2::new(scale, scale),
+			//Images are tinted by the current color unless given one
+			color: Some([1.0, 1.0, 1.0, 1.0].into()),
+			..Default::default()
+		}).expect("Error drawing image");
+	}
+
+	fn text(&mut self, text: &str, x: f32, y: f32) -> (f32, f32) {
+		let text = &graphics::Text::new(self.ctx, text, &self.font).expect("Error generating text");
+		graphics::draw(self.ctx, text, Point2::new(x, y), 0.0).expect("Error drawing text");
+		(text.width() as f32, text.height() as f32)
+	}
+
+	fn text_size(&mut self, text: &str) -> (f32, f32) {
+		let text = graphics::Text::new(self.ctx, text, &self.font).expect("Error generating text");
+		(text.width() as f32, text.height() as f32)
+	}
+}
+
+///A single call made to a MemoryRenderer
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCall {
+	SetColor(Color),
+	FillRect(f32, f32, f32, f32),
+	Line((f32, f32), (f32, f32), f32),
+	Polygon(Vec<(f32, f32)>),
+	Pixels(f32, f32, u16, u16, Vec<u8>, f32),
+	Text(String, f32, f32),
+}
+
+///Renderer that records draw calls instead of painting them
+pub struct MemoryRenderer {
+	calls: Vec<DrawCall>,
+}
+
+impl MemoryRenderer {
+	/// Used only in testing.
+	/// Generates an empty recording.
+	#[allow(dead_code)]
+	pub fn new() -> Self {
+		MemoryRenderer { calls: Vec::new() }
+	}
+
+	/// Used only in testing.
+	/// Returns every recorded call in the order it was made
+	#[allow(dead_code)]
+	pub fn calls(&self) -> &Vec<DrawCall> {
+		&self.calls
+	}
+
+	/// Used only in testing.
+	/// Returns every filled rectangle paired with the color it was drawn in
+	#[allow(dead_code)]
+	pub fn rects(&self) -> Vec<(Color, (f32, f32, f32, f32))> {
+		let mut color = [1.0, 1.0, 1.0, 1.0];
+		let mut rects = Vec::new();
+		for call in self.calls.iter() {
+			match *call {
+				DrawCall::SetColor(c) => color = c,
+				DrawCall::FillRect(x, y, w, h) => rects.push((color, (x, y, w, h))),
+				_ => (),
+			}
+		}
+		rects
+	}
+
+	/// Used only in testing.
+	/// Returns every filled polygon paired with the color it was drawn in
+	#[allow(dead_code)]
+	pub fn polygons(&self) -> Vec<(Color, Vec<(f32, f32)>)> {
+		let mut color = [1.0, 1.0, 1.0, 1.0];
+		let mut polygons = Vec::new();
+		for call in self.calls.iter() {
+			match *call {
+				DrawCall::SetColor(c) => color = c,
+				DrawCall::Polygon(ref points) => polygons.push((color, points.clone())),
+				_ => (),
+			}
+		}
+		polygons
+	}
+
+	/// Used only in testing.
+	/// Returns every string drawn, in order
+	#[allow(dead_code)]
+	pub fn texts(&self) -> Vec<String> {
+		self.calls.iter().filter_map(|call| match *call {
+			DrawCall::Text(ref text, _, _) => Some(text.clone()),
+			_ => None,
+		}).collect()
+	}
+}
+
+impl Renderer for MemoryRenderer {
+	fn set_color(&mut self, color: Color) {
+		self.calls.push(DrawCall::SetColor(color));
+	}
+
+	fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32) {
+		self.calls.push(DrawCall::FillRect(x, y, w, h));
+	}
+
+	fn line(&mut self, from: (f32, f32), to: (f32, f32), width: f32) {
+		self.calls.push(DrawCall::Line(from, to, width));
+	}
+
+	fn fill_polygon(&mut self, points: &[(f32, f32)]) {
+		self.calls.push(DrawCall::Polygon(points.to_vec()));
+	}
+
+	fn pixels(&mut self, x: f32, y: f32, width: u16, height: u16, rgba: &[u8], scale: f32) {
+		self.calls.push(DrawCall::Pixels(x, y, width, height, rgba.to_vec(), scale));
+	}
+
+	fn text(&mut self, text: &str, x: f32, y: f32) -> (f32, f32) {
+		self.calls.push(DrawCall::Text(text.to_string(), x, y));
+		self.text_size(text)
+	}
+
+	fn text_size(&mut self, text: &str) -> (f32, f32) {
+		(text.chars().count() as f32 * SIZE_MEMORY_CHAR.0, SIZE_MEMORY_CHAR.1)
+	}
+}
+
+#[test]
+fn test_gradient() {
+	let gradient = Gradient::new(vec![(9, [0.0, 1.0, 0.0, 1.0]), (1, [1.0, 0.0, 0.0, 1.0]), (5, [0.0, 0.0, 1.0, 0.5])]);
+
+	//Stops given out of order are sorted, and numbers outside of them are clamped
+	assert_eq!([1.0, 0.0, 0.0, 1.0], gradient.color(0));
+	assert_eq!([1.0, 0.0, 0.0, 1.0], gradient.color(1));
+	assert_eq!([0.0, 0.0, 1.0, 0.5], gradient.color(5));
+	assert_eq!([0.0, 1.0, 0.0, 1.0], gradient.color(1000));
+
+	//Numbers between stops blend them
+	assert_eq!([0.75, 0.0, 0.25, 0.875], gradient.color(2));
+	assert_eq!([0.0, 0.5, 0.5, 0.75], gradient.color(7));
+	assert_eq!([0.5, 0.5, 0.5, 1.0], Gradient::new(vec![(3, [0.5, 0.5, 0.5, 1.0])]).color(8));
+
+	//Stops are read as number:RRGGBB with optional alpha
+	assert_eq!(Ok(Gradient::new(vec![(1, [1.0, 0.0, 0.0, 1.0]), (10, [0.0, 0.0, 1.0, 0.0])])), Gradient::parse("10:#0000ff00, 1:FF0000"));
+	assert!(Gradient::parse("").is_err());
+	assert!(Gradient::parse("1:ff0000, 2").is_err());
+	assert!(Gradient::parse("one:ff0000").is_err());
+	assert!(Gradient::parse("1:ff00").is_err());
+	assert!(Gradient::parse("1:gg0000").is_err());
+}
+
+#[test]
+fn test_memory_renderer_records_calls() {
+	let mut renderer = MemoryRenderer::new();
+	renderer.set_color([0.1, 0.2, 0.3, 1.0]);
+	renderer.fill_rect(1.0, 2.0, 3.0, 4.0);
+	assert_eq!((40.0, 24.0), renderer.text("Test", 5.0, 6.0));
+
+	assert_eq!(vec![([0.1, 0.2, 0.3, 1.0], (1.0, 2.0, 3.0, 4.0))], renderer.rects());
+	assert_eq!(vec!["Test".to_string()], renderer.texts());
+	assert_eq!(3, renderer.calls().len());
+}
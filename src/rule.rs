@@ -0,0 +1,949 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Life-like rules written in B/S notation. The classic Game of Life is B3/S23:
+// a dead cell is born with exactly 3 living neighbors and a living cell survives
+// with 2 or 3.
+//
+// Generations rules add a count of states, such as B2/S/C3 for Brian's Brain.
+// A living cell that doesn't survive starts dying instead of dying straight away,
+// passing through each state after alive, one a generation, until it is dead.
+// Dying cells don't count as living neighbors and can't be born again until dead.
+//
+// Larger than Life rules count neighbors further away, written such as
+// R5,C0,M1,S34..58,B34..45,NM for Bosco's rule: a radius of 5, 2 states, the cell
+// itself counted as its own neighbor, survival with 34 to 58 living neighbors, birth
+// with 34 to 45, in the square (NM) range rather than the diamond (NN) range.
+//
+// A B/S rule ending in V counts only the four cells sharing an edge, and one ending
+// in H counts the six cells around on a hex grid. Other neighborhoods can be given
+// to a B/S rule, but have no letter, so the rule string doesn't show them.
+//
+// Isotropic non-totalistic rules tell apart the arrangements of each count of
+// neighbors, written in Hensel notation with a letter for each arrangement after
+// the count, such as B2-a/S12: birth with 2 neighbors unless they are next to each
+// other (2a), survival with 1 or 2. A - takes the letters after it away instead. These
+// rules are compiled to a table of the next state for every 3x3 block of cells.
+//
+// WireWorld models electrons running along wires, with four states: empty, electron
+// head, electron tail and conductor. Empty cells stay empty, a head becomes a tail, a
+// tail becomes a conductor, and a conductor becomes a head when 1 or 2 of the eight
+// cells around it are heads. Heads are the living cells, so they are what is counted.
+//
+// Immigration and QuadLife are the classic Game of Life with living cells in two or
+// four colors, for games between players. A cell born takes the color most of its
+// three parents have, and under QuadLife three parents of three colors give the
+// fourth. Surviving cells keep their color.
+//
+// One dimensional rules, such as W30, grow a space-time diagram a row at a time instead,
+// as described in elementary.rs.
+
+use std::fmt;
+
+use elementary::{Elementary, Boundary};
+use neighborhood::{Neighborhood};
+
+///State of a cell. Dead cells are 0 and living cells are 1, and rules with more
+///states use the numbers after 1 for cells that are dying.
+pub type State = u8;
+
+pub const DEAD: State = 0;
+pub const ALIVE: State = 1;
+
+///States of WireWorld cells, numbered as in Golly's WireWorld rule
+pub const HEAD: State = ALIVE;
+pub const TAIL: State = 2;
+pub const CONDUCTOR: State = 3;
+
+///Most colors living cells can come in, under QuadLife
+pub const MAX_COLORS: usize = 4;
+
+///Largest radius of a Larger than Life neighborhood
+pub const MAX_RADIUS: i32 = 10;
+
+///Bit of the cell itself in a 3x3 block, read as 9 bits from the top left in reading order
+const MIDDLE: usize = 1 << 4;
+///Number of different 3x3 blocks
+const BLOCKS: usize = 1 << 9;
+///Bits of the eight cells around the middle of a 3x3 block
+const RING: usize = (BLOCKS - 1) & !MIDDLE;
+///Where each bit of a 3x3 block moves to when the block is turned a quarter clockwise
+const ROTATE: [usize; 9] = [2, 5, 8, 1, 4, 7, 0, 3, 6];
+///Where each bit of a 3x3 block moves to when the block is flipped left to right
+const MIRROR: [usize; 9] = [2, 1, 0, 5, 4, 3, 8, 7, 6];
+
+///Letters of the arrangements of each count of neighbors in Hensel notation, in the order of HENSEL_BLOCKS
+const HENSEL_LETTERS: [&str; 9] = ["", "ce", "ceaikn", "ceaiknjqry", "ceaiknjqrytwz", "ceaiknjqry", "ceaikn", "ce", ""];
+///One block with each arrangement of 1 to 4 neighbors. The arrangements of 5 to 7 are the rings left around 3 to 1.
+const HENSEL_BLOCKS: [&[usize]; 5] = [
+	&[],
+	&[1, 2],
+	&[5, 10, 3, 40, 33, 68],
+	&[69, 42, 11, 7, 98, 13, 14, 70, 41, 97],
+	&[325, 170, 15, 45, 99, 71, 106, 102, 43, 101, 105, 78, 108],
+];
+
+///Shape of the cells within the radius of a cell that count as its neighbors, under Larger than Life rules
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Range {
+	///Every cell in the square around the cell, NM
+	Moore,
+	///Cells no more than the radius away counting steps along rows and columns, a diamond, NN
+	VonNeumann,
+}
+
+impl Range {
+	/// Counts the cells in the range, not counting the cell in the middle.
+	///
+	/// # Arguments
+	/// * 'radius' - How far the range reaches from the cell in the middle.
+	pub fn size(&self, radius: i32) -> i32 {
+		match *self {
+			Range::Moore => (2 * radius + 1) * (2 * radius + 1) - 1,
+			Range::VonNeumann => 2 * radius * (radius + 1),
+		}
+	}
+}
+
+///Defines which neighbor counts give birth to dead cells and which keep living cells alive
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+	///birth[n] is true if a dead cell with n living neighbors comes to life
+	birth: Vec<bool>,
+	///survival[n] is true if a living cell with n living neighbors stays alive
+	survival: Vec<bool>,
+	///Number of states a cell can be in, counting dead and alive. 2 for Life-like rules.
+	states: State,
+	///How far away neighbors can be. 1 for Life-like rules.
+	radius: i32,
+	///Shape of the neighbors within the radius
+	range: Range,
+	///True if a living cell counts itself as one of its neighbors
+	middle: bool,
+	///Cells counted as neighbors, and how much each counts
+	neighborhood: Neighborhood,
+	///Under isotropic non-totalistic rules, whether a cell is alive next for each 3x3 block around
+	///it, indexed by the block's bits. The birth and survival counts are left empty.
+	table: Option<Vec<bool>>,
+	///True for WireWorld, which moves every state on by its own rule instead of the counts
+	wireworld: bool,
+	///Number of colors living cells come in, 0 for rules without colors
+	colors: u8,
+	///The one dimensional rule that grows the map a row at a time, None for rules over the whole map
+	line: Option<Elementary>,
+}
+
+impl Rule {
+	/// Generates the classic Game of Life rule, B3/S23
+	pub fn classic() -> Self {
+		Rule::parse("B3/S23").expect("Classic rule is valid")
+	}
+
+	/// Generates WireWorld, with Moore neighborhood counts of electron heads
+	pub fn wireworld() -> Self {
+		Rule {
+			birth: Vec::new(),
+			survival: Vec::new(),
+			states: 4,
+			radius: 1,
+			range: Range::Moore,
+			middle: false,
+			neighborhood: Neighborhood::moore(),
+			table: None,
+			wireworld: true,
+			colors: 0,
+			line: None,
+		}
+	}
+
+	/// Generates the classic Game of Life with living cells in colors, Immigration for 2 and QuadLife for 4
+	///
+	/// # Arguments
+	/// * 'colors' - The number of colors, 2 or 4.
+	pub fn colored(colors: u8) -> Self {
+		Rule { colors, ..Rule::classic() }
+	}
+
+	/// Parses a rule string in B/S notation, such as "B3/S23" or "B36/S23", Hensel notation,
+	/// such as "B3/S2-i34q", B/S/C notation
+	/// for Generations rules, such as "B2/S/C3", B/S notation ending in V for the von Neumann
+	/// neighborhood or H for hexagonal grids, such as "B2/S34H", or Larger than Life notation, such as
+	/// "R5,C0,M1,S34..58,B34..45,NM", "WireWorld", "Immigration" or "QuadLife", or a one dimensional
+	/// rule, such as "W30" or "T10R2". The parts may come in any order and are case insensitive.
+	///
+	/// # Arguments
+	/// * 'text' - The rule string.
+	///
+	/// # Return
+	/// * Result<Rule, String> - The rule, or a description of what is wrong with the string.
+	pub fn parse(text: &str) -> Result<Rule, String> {
+		match text.trim().to_lowercase().as_str() {
+			"wireworld" => return Ok(Rule::wireworld()),
+			"immigration" => return Ok(Rule::colored(2)),
+			"quadlife" => return Ok(Rule::colored(4)),
+			_ => (),
+		}
+		//One dimensional rules start with W or T and a number
+		let mut chars = text.trim().chars();
+		let first = chars.next().map(|c| c.to_ascii_uppercase());
+		if (first == Some('W') || first == Some('T')) && chars.next().is_some_and(|c| c.is_ascii_digit()) {
+			return Elementary::parse(text).map(|line| Rule { line: Some(line), ..Rule::classic() });
+		}
+		if text.contains(',') {
+			Rule::parse_larger(text)
+		}
+		else {
+			Rule::parse_life_like(text)
+		}
+	}
+
+	/// Parses a rule string in B/S or B/S/C notation, with a V or H on the end for the von Neumann
+	/// neighborhood or hexagonal grids. Letters after the counts make it isotropic non-totalistic.
+	///
+	/// # Arguments
+	/// * 'text' - The rule string.
+	///
+	/// # Return
+	/// * Result<Rule, String> - The rule, or a description of what is wrong with the string.
+	fn parse_life_like(text: &str) -> Result<Rule, String> {
+		let mut birth = None;
+		let mut survival = None;
+		let mut states = None;
+
+		//A letter on the end picks a neighborhood with fewer than eight neighbors
+		let text = text.trim();
+		let (text, neighborhood) = match text.chars().last().map(|c| c.to_ascii_uppercase()) {
+			Some('V') => (&text[..text.len() - 1], Neighborhood::von_neumann()),
+			Some('H') => (&text[..text.len() - 1], Neighborhood::hexagonal()),
+			_ => (text, Neighborhood::moore()),
+		};
+		let most = neighborhood.most() as usize;
+
+		for part in text.split('/') {
+			let part = part.trim();
+			let mut chars = part.chars();
+			let counts = match chars.next() {
+				Some('B') | Some('b') if birth.is_none() => &mut birth,
+				Some('S') | Some('s') if survival.is_none() => &mut survival,
+				Some('C') | Some('c') if states.is_none() => {
+					match chars.as_str().parse::<State>() {
+						Ok(count) if count >= 2 => states = Some(count),
+						_ => return Err(format!("'{}' is not a count of states from 2 to {}", chars.as_str(), State::MAX)),
+					}
+					continue;
+				},
+				_ => return Err(format!("expected B.../S..., found '{}'", part)),
+			};
+
+			*counts = Some(chars.as_str());
+		}
+
+		let (birth, survival) = match (birth, survival) {
+			(Some(birth), Some(survival)) => (birth, survival),
+			_ => return Err("expected B.../S...".to_string()),
+		};
+		let mut rule = Rule {
+			birth: Vec::new(),
+			survival: Vec::new(),
+			states: states.unwrap_or(2),
+			radius: 1,
+			range: if neighborhood == Neighborhood::von_neumann() { Range::VonNeumann } else { Range::Moore },
+			middle: false,
+			neighborhood,
+			table: None,
+			wireworld: false,
+			colors: 0,
+			line: None,
+		};
+
+		//Letters pick out arrangements of the eight cells around
+		if birth.chars().chain(survival.chars()).any(|c| !c.is_ascii_digit()) {
+			if rule.neighborhood != Neighborhood::moore() {
+				return Err("letters for arrangements of neighbors only work with the eight cells around".to_string());
+			}
+			let (birth, survival) = (arrangements(birth)?, arrangements(survival)?);
+			//Rules choosing every arrangement of a count, or none, are plain B/S rules
+			let totalistic = |chosen: &Vec<bool>| (0..BLOCKS).all(|block| chosen[block & RING] == chosen[hensel_block((block & RING).count_ones() as usize, 0)]);
+			if !totalistic(&birth) || !totalistic(&survival) {
+				rule.table = Some((0..BLOCKS).map(|block| match block & MIDDLE {
+					0 => birth[block],
+					_ => survival[block & RING],
+				}).collect());
+				return Ok(rule);
+			}
+			let counts = |chosen: &Vec<bool>| (0..9).map(|count| chosen[hensel_block(count, 0)]).collect();
+			rule.birth = counts(&birth);
+			rule.survival = counts(&survival);
+			return Ok(rule);
+		}
+
+		let counts = |text: &str| {
+			let mut neighbors = vec![false; most + 1];
+			for c in text.chars() {
+				match c.to_digit(10) {
+					Some(n) if n as usize <= most => neighbors[n as usize] = true,
+					_ => return Err(format!("'{}' is not a neighbor count", c)),
+				}
+			}
+			Ok(neighbors)
+		};
+		rule.birth = counts(birth)?;
+		rule.survival = counts(survival)?;
+		Ok(rule)
+	}
+
+	/// Parses a rule string in Larger than Life notation, "Rr,Cc,Mm,Ssmin..smax,Bbmin..bmax,Nn".
+	/// C, M and N may be left out for 2 states, M0 and NM.
+	///
+	/// # Arguments
+	/// * 'text' - The rule string.
+	///
+	/// # Return
+	/// * Result<Rule, String> - The rule, or a description of what is wrong with the string.
+	fn parse_larger(text: &str) -> Result<Rule, String> {
+		let mut radius = None;
+		let mut states = None;
+		let mut middle = None;
+		let mut survival = None;
+		let mut birth = None;
+		let mut range = None;
+
+		//Reads a count such as 34, or an interval such as 34..58
+		let number = |text: &str| text.trim().parse::<i32>().map_err(|_| format!("'{}' is not a number", text));
+		let interval = |text: &str| {
+			let mut ends = text.splitn(2, "..");
+			let low = number(ends.next().unwrap_or(""))?;
+			let high = ends.next().map_or(Ok(low), &number)?;
+			if low > high {
+				return Err(format!("'{}' is an empty interval", text));
+			}
+			Ok((low, high))
+		};
+
+		for part in text.trim().split(',') {
+			let part = part.trim();
+			let mut chars = part.chars();
+			let first = chars.next().map(|c| c.to_ascii_uppercase());
+			let rest = chars.as_str();
+			match first {
+				Some('R') if radius.is_none() => radius = Some(number(rest)?),
+				Some('C') if states.is_none() => states = Some(number(rest)?),
+				Some('M') if middle.is_none() => middle = Some(number(rest)?),
+				Some('S') if survival.is_none() => survival = Some(interval(rest)?),
+				Some('B') if birth.is_none() => birth = Some(interval(rest)?),
+				Some('N') if range.is_none() => range = Some(match rest.to_uppercase().as_str() {
+					"M" => Range::Moore,
+					"N" => Range::VonNeumann,
+					_ => return Err(format!("'{}' is not a range, expected NM or NN", part)),
+				}),
+				_ => return Err(format!("expected Rr,Cc,Mm,Ss..s,Bb..b,Nn, found '{}'", part)),
+			}
+		}
+
+		let radius = radius.ok_or("expected a radius, such as R5")?;
+		if !(1..=MAX_RADIUS).contains(&radius) {
+			return Err(format!("the radius must be from 1 to {}", MAX_RADIUS));
+		}
+		//C0 and C1 both mean plain alive and dead
+		let states = match states.unwrap_or(0) {
+			0 | 1 => 2,
+			count if count <= State::MAX as i32 => count as State,
+			count => return Err(format!("'{}' is not a count of states from 2 to {}", count, State::MAX)),
+		};
+		let middle = match middle.unwrap_or(0) {
+			0 => false,
+			1 => true,
+			_ => return Err("M must be 0 or 1".to_string()),
+		};
+		let range = range.unwrap_or(Range::Moore);
+
+		//Counts can't go past every neighbor alive
+		let most = range.size(radius) + if middle { 1 } else { 0 };
+		let counts = |interval: Option<(i32, i32)>, name: &str| {
+			let (low, high) = interval.ok_or_else(|| format!("expected {} counts, such as {}2..3", name, &name[..1]))?;
+			if low < 0 || high > most {
+				return Err(format!("{} counts must be from 0 to {}", name, most));
+			}
+			Ok((0..most + 1).map(|n| n >= low && n <= high).collect::<Vec<bool>>())
+		};
+		Ok(Rule {
+			survival: counts(survival, "Survival")?,
+			birth: counts(birth, "Birth")?,
+			states,
+			radius,
+			range,
+			middle,
+			neighborhood: Neighborhood::range(range, radius, middle),
+			table: None,
+			wireworld: false,
+			colors: 0,
+			line: None,
+		})
+	}
+
+	/// Returns the number of states a cell can be in, counting dead and alive
+	pub fn states(&self) -> State {
+		self.states
+	}
+
+	/// Returns how far away neighbors can be
+	pub fn radius(&self) -> i32 {
+		self.radius
+	}
+
+	/// Returns the shape of the neighbors within the radius
+	pub fn range(&self) -> Range {
+		self.range
+	}
+
+	/// Returns true if a living cell counts itself as one of its neighbors
+	pub fn middle(&self) -> bool {
+		self.middle
+	}
+
+	/// Returns the cells counted as neighbors
+	pub fn neighborhood(&self) -> &Neighborhood {
+		&self.neighborhood
+	}
+
+	/// Returns true for WireWorld
+	pub fn is_wireworld(&self) -> bool {
+		self.wireworld
+	}
+
+	/// Returns the one dimensional rule growing the map a row at a time, None for rules over the whole map
+	pub fn line(&self) -> Option<Elementary> {
+		self.line
+	}
+
+	/// Generates the same one dimensional rule with a different boundary.
+	///
+	/// # Arguments
+	/// * 'boundary' - What the cells past the ends of a row are taken to be.
+	///
+	/// # Return
+	/// * Result<Rule, String> - The rule, or why it has no boundary.
+	pub fn with_boundary(&self, boundary: Boundary) -> Result<Rule, String> {
+		match self.line {
+			Some(line) => Ok(Rule { line: Some(line.with_boundary(boundary)), ..self.clone() }),
+			None => Err("only one dimensional rules have a boundary, such as W30".to_string()),
+		}
+	}
+
+	/// Returns the number of colors living cells come in, 0 for rules without colors
+	pub fn colors(&self) -> u8 {
+		self.colors
+	}
+
+	/// Picks the color of a cell being born from the colors of its parents, the living cells
+	/// around it: the color most of them have, or under QuadLife, the color none of three
+	/// parents of three colors have. Ties go to the first color.
+	///
+	/// # Arguments
+	/// * 'parents' - How many parents have each color, counted by weight.
+	///
+	/// # Return
+	/// * u8 - The color of the cell being born.
+	pub fn newborn_color(&self, parents: &[i32]) -> u8 {
+		let most = parents.iter().cloned().max().unwrap_or(0);
+		if self.colors == 4 && most == 1 {
+			if let Some(missing) = parents.iter().position(|count| *count == 0) {
+				return missing as u8;
+			}
+		}
+		parents.iter().position(|count| *count == most).unwrap_or(0) as u8
+	}
+
+	/// Returns the state the pen paints, a conductor under WireWorld and alive otherwise
+	pub fn painted(&self) -> State {
+		if self.wireworld { CONDUCTOR } else { ALIVE }
+	}
+
+	/// Generates the same B/S rule counting a different neighborhood. Counts higher than the
+	/// neighborhood can reach are dropped.
+	///
+	/// # Arguments
+	/// * 'neighborhood' - The cells to count as neighbors.
+	///
+	/// # Return
+	/// * Result<Rule, String> - The rule, or why the neighborhood can't be used with it.
+	pub fn with_neighborhood(&self, neighborhood: Neighborhood) -> Result<Rule, String> {
+		if self.radius > 1 || self.middle {
+			return Err("Larger than Life rules count their own range, such as NM or NN".to_string());
+		}
+		if self.line.is_some() {
+			return Err("one dimensional rules count the cells along their row".to_string());
+		}
+		if self.wireworld || self.colors > 0 {
+			if neighborhood != Neighborhood::moore() {
+				return Err(format!("{} only counts the eight cells around", self));
+			}
+			return Ok(self.clone());
+		}
+		if self.table.is_some() {
+			if neighborhood != Neighborhood::moore() {
+				return Err("isotropic rules only count the eight cells around".to_string());
+			}
+			return Ok(self.clone());
+		}
+		let counts = |counts: &Vec<bool>| {
+			(0..neighborhood.most() as usize + 1).map(|n| counts.get(n).cloned().unwrap_or(false)).collect()
+		};
+		Ok(Rule {
+			birth: counts(&self.birth),
+			survival: counts(&self.survival),
+			states: self.states,
+			radius: 1,
+			range: if neighborhood == Neighborhood::von_neumann() { Range::VonNeumann } else { Range::Moore },
+			middle: false,
+			neighborhood,
+			table: None,
+			wireworld: false,
+			colors: 0,
+			line: None,
+		})
+	}
+
+	/// Counts the living neighbors around a target in a map of coordinates, as Rule::next takes them.
+	///
+	/// # Arguments
+	/// * 'map' - The map of coordinates.
+	/// * 'x & y' - The target coordinate.
+	///
+	/// # Return
+	/// * i32 - The weighted number of living neighbors, or under isotropic non-totalistic rules
+	///   the bits of the 3x3 block around the target, itself included.
+	pub fn neighbors(&self, map: &[Vec<State>], x: i32, y: i32) -> i32 {
+		if self.table.is_none() {
+			return self.neighborhood.count(map, x, y);
+		}
+		let width = map.first().map_or(0, |row| row.len()) as i32;
+		let height = map.len() as i32;
+		let mut block = 0;
+		for bit in 0..9 {
+			let (x, y) = (x + bit % 3 - 1, y + bit / 3 - 1);
+			if x >= 0 && y >= 0 && x < width && y < height && map[y as usize][x as usize] == ALIVE {
+				block |= 1 << bit;
+			}
+		}
+		block
+	}
+
+	/// Decides the next state of a cell.
+	///
+	/// # Arguments
+	/// * 'state'     - The state of the cell this generation.
+	/// * 'neighbors' - The number of living neighbors around the cell, as given by Rule::neighbors.
+	///
+	/// # Return
+	/// * State - The state of the cell next generation.
+	pub fn next(&self, state: State, neighbors: i32) -> State {
+		if self.wireworld {
+			return match state {
+				HEAD => TAIL,
+				TAIL => CONDUCTOR,
+				CONDUCTOR if neighbors == 1 || neighbors == 2 => HEAD,
+				state => state,
+			};
+		}
+		//The middle bit of a block tells births from survivals, so both look in the one table
+		let any = |counts: &Vec<bool>| self.table.as_ref().unwrap_or(counts).get(neighbors as usize).cloned().unwrap_or(false);
+		match state {
+			DEAD if any(&self.birth) => ALIVE,
+			DEAD => DEAD,
+			ALIVE if any(&self.survival) => ALIVE,
+			//Dying cells pass through every state left, then die
+			_ if state + 1 < self.states => state + 1,
+			_ => DEAD,
+		}
+	}
+}
+
+impl fmt::Display for Rule {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(line) = self.line {
+			return write!(f, "{}", line);
+		}
+		match (self.wireworld, self.colors) {
+			(true, _) => return write!(f, "WireWorld"),
+			(_, 2) => return write!(f, "Immigration"),
+			(_, 4) => return write!(f, "QuadLife"),
+			_ => (),
+		}
+		//Rules that B/S notation can't describe are written in Larger than Life notation
+		if self.radius > 1 || self.middle {
+			let interval = |counts: &Vec<bool>| {
+				let low = counts.iter().position(|any| *any).unwrap_or(0);
+				let high = counts.iter().rposition(|any| *any).unwrap_or(0);
+				format!("{}..{}", low, high)
+			};
+			return write!(f, "R{},C{},M{},S{},B{},N{}",
+				self.radius,
+				if self.states > 2 { self.states } else { 0 },
+				if self.middle { 1 } else { 0 },
+				interval(&self.survival),
+				interval(&self.birth),
+				if self.range == Range::Moore { "M" } else { "N" }
+			);
+		}
+
+		match self.table {
+			Some(ref table) => write!(f, "B{}/S{}", hensel_counts(table, 0), hensel_counts(table, MIDDLE))?,
+			None => {
+				let counts = |neighbors: &Vec<bool>| {
+					(0..neighbors.len()).filter(|n| neighbors[*n]).map(|n| n.to_string()).collect::<String>()
+				};
+				write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))?;
+			},
+		}
+		if self.states > 2 {
+			write!(f, "/C{}", self.states)?;
+		}
+		if self.neighborhood == Neighborhood::von_neumann() {
+			write!(f, "V")?;
+		}
+		else if self.neighborhood == Neighborhood::hexagonal() {
+			write!(f, "H")?;
+		}
+		Ok(())
+	}
+}
+
+/// Finds the block of one arrangement in Hensel notation.
+///
+/// # Arguments
+/// * 'count'  - The number of living cells around the middle.
+/// * 'letter' - The place of the arrangement's letter in HENSEL_LETTERS. Ignored for 0 and 8.
+///
+/// # Return
+/// * usize - A block with that arrangement around an empty middle.
+fn hensel_block(count: usize, letter: usize) -> usize {
+	match count {
+		0 => 0,
+		8 => RING,
+		count if count <= 4 => HENSEL_BLOCKS[count][letter],
+		count => RING ^ HENSEL_BLOCKS[8 - count][letter],
+	}
+}
+
+/// Names the arrangement of the cells around the middle of a block in Hensel notation.
+///
+/// # Arguments
+/// * 'block' - The bits of the 3x3 block.
+///
+/// # Return
+/// * (usize, Option<char>) - The count of living cells around the middle, and the letter of their arrangement, None for 0 and 8.
+fn hensel(block: usize) -> (usize, Option<char>) {
+	let ring = block & RING;
+	let count = ring.count_ones() as usize;
+	//Turn and flip the block every way until it matches an arrangement
+	let moved = |block: usize, to: &[usize; 9]| (0..9).filter(|bit| block & 1 << bit != 0).fold(0, |moved, bit| moved | 1 << to[bit]);
+	for (i, letter) in HENSEL_LETTERS[count].chars().enumerate() {
+		let mut turned = hensel_block(count, i);
+		for _ in 0..4 {
+			if turned == ring || moved(turned, &MIRROR) == ring {
+				return (count, Some(letter));
+			}
+			turned = moved(turned, &ROTATE);
+		}
+	}
+	(count, None)
+}
+
+/// Reads the counts and letters of the birth or survival part of a rule in Hensel notation.
+///
+/// # Arguments
+/// * 'text' - The part, such as "2-a3" or "34q", without the B or S.
+///
+/// # Return
+/// * Result<Vec<bool>, String> - Whether each block is chosen, going by the cells around the middle, or a description of what is wrong with the part.
+fn arrangements(text: &str) -> Result<Vec<bool>, String> {
+	let mut chosen = vec![false; BLOCKS];
+	let mut chars = text.chars().peekable();
+	while let Some(c) = chars.next() {
+		let count = match c.to_digit(10) {
+			Some(n) if n <= 8 => n as usize,
+			_ => return Err(format!("'{}' is not a neighbor count", c)),
+		};
+		let without = chars.peek() == Some(&'-');
+		if without {
+			chars.next();
+		}
+		let mut letters = String::new();
+		while let Some(&c) = chars.peek() {
+			if c.is_ascii_digit() {
+				break;
+			}
+			if !HENSEL_LETTERS[count].contains(c.to_ascii_lowercase()) {
+				return Err(format!("'{}' is not an arrangement of {} neighbors", c, count));
+			}
+			letters.push(c.to_ascii_lowercase());
+			chars.next();
+		}
+		if without && letters.is_empty() {
+			return Err(format!("expected letters after {}-", count));
+		}
+
+		//No letters chooses every arrangement of the count
+		for (block, chosen) in chosen.iter_mut().enumerate() {
+			let (n, letter) = hensel(block);
+			if n == count && (letters.is_empty() || letter.is_some_and(|letter| letters.contains(letter)) != without) {
+				*chosen = true;
+			}
+		}
+	}
+	Ok(chosen)
+}
+
+/// Writes the birth or survival part of an isotropic non-totalistic rule in Hensel notation,
+/// with whichever of the letters chosen or the letters left out is shorter.
+///
+/// # Arguments
+/// * 'table'  - The next state of each block.
+/// * 'middle' - 0 for the birth part, MIDDLE for the survival part.
+fn hensel_counts(table: &[bool], middle: usize) -> String {
+	let mut text = String::new();
+	for count in 0..9 {
+		let letters = HENSEL_LETTERS[count];
+		if letters.is_empty() {
+			if table[middle | hensel_block(count, 0)] {
+				text += count.to_string().as_str();
+			}
+			continue;
+		}
+		let mut chosen: Vec<char> = letters.chars().enumerate().filter(|&(i, _)| table[middle | hensel_block(count, i)]).map(|(_, letter)| letter).collect();
+		let mut left: Vec<char> = letters.chars().filter(|letter| !chosen.contains(letter)).collect();
+		chosen.sort();
+		left.sort();
+		if chosen.is_empty() {
+			continue;
+		}
+		text += count.to_string().as_str();
+		if left.is_empty() {
+			continue;
+		}
+		if chosen.len() <= left.len() {
+			text.extend(chosen);
+		}
+		else {
+			text.push('-');
+			text.extend(left);
+		}
+	}
+	text
+}
+
+#[test]
+fn test_rule_parse() {
+	let classic = Rule::classic();
+	assert_eq!(ALIVE, classic.next(DEAD, 3));
+	assert_eq!(DEAD, classic.next(DEAD, 2));
+	assert!(classic.next(ALIVE, 2) == ALIVE && classic.next(ALIVE, 3) == ALIVE);
+	assert_eq!(DEAD, classic.next(ALIVE, 4));
+	assert_eq!(2, classic.states());
+
+	//Order and case don't matter, and rules print in a standard form
+	assert_eq!(Ok(classic.clone()), Rule::parse(" s23/b3 "));
+	assert_eq!("B36/S23", Rule::parse("B63/S32").unwrap().to_string());
+	assert_eq!("B2/S", Rule::parse("B2/S").unwrap().to_string());
+
+	assert_eq!(Err("'9' is not a neighbor count".to_string()), Rule::parse("B9/S23"));
+	assert_eq!(Err("expected B.../S...".to_string()), Rule::parse("B3"));
+	assert!(Rule::parse("B3/B3").is_err());
+	assert!(Rule::parse("23/3").is_err());
+}
+
+#[test]
+fn test_rule_generations() {
+	//Brian's Brain: cells are born with 2 neighbors, never survive and spend a generation dying
+	let brain = Rule::parse("b2/s/c3").unwrap();
+	assert_eq!("B2/S/C3", brain.to_string());
+	assert_eq!(3, brain.states());
+	assert_eq!((ALIVE, DEAD), (brain.next(DEAD, 2), brain.next(DEAD, 3)));
+	assert_eq!((2, 2), (brain.next(ALIVE, 2), brain.next(ALIVE, 8)));
+	assert_eq!((DEAD, DEAD), (brain.next(2, 2), brain.next(2, 0)));
+
+	//Star Wars: survivors stay, the rest dies over three generations
+	let star_wars = Rule::parse("C4/S345/B2").unwrap();
+	assert_eq!("B2/S345/C4", star_wars.to_string());
+	assert_eq!((ALIVE, 2, 3, DEAD), (star_wars.next(ALIVE, 4), star_wars.next(ALIVE, 2), star_wars.next(2, 4), star_wars.next(3, 2)));
+
+	//C2 is just a Life-like rule
+	assert_eq!(Ok(Rule::classic()), Rule::parse("B3/S23/C2"));
+	assert!(Rule::parse("B2/S/C1").is_err());
+	assert!(Rule::parse("B2/S/C").is_err());
+	assert!(Rule::parse("B2/S/C300").is_err());
+	assert!(Rule::parse("B2/S/C3/C4").is_err());
+}
+
+#[test]
+fn test_rule_larger_than_life() {
+	//Bosco's rule
+	let bosco = Rule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+	assert_eq!("R5,C0,M1,S34..58,B34..45,NM", bosco.to_string());
+	assert_eq!((5, Range::Moore, true, 2), (bosco.radius(), bosco.range(), bosco.middle(), bosco.states()));
+	assert_eq!((DEAD, ALIVE, ALIVE, DEAD), (bosco.next(DEAD, 33), bosco.next(DEAD, 34), bosco.next(DEAD, 45), bosco.next(DEAD, 46)));
+	assert_eq!((DEAD, ALIVE, ALIVE, DEAD), (bosco.next(ALIVE, 33), bosco.next(ALIVE, 34), bosco.next(ALIVE, 58), bosco.next(ALIVE, 59)));
+	assert_eq!(DEAD, bosco.next(ALIVE, 121));
+
+	//Parts come in any order, and C, M and N have defaults
+	let diamond = Rule::parse("nn, b3..4, s2, r2, c3").unwrap();
+	assert_eq!("R2,C3,M0,S2..2,B3..4,NN", diamond.to_string());
+	assert_eq!((12, 24), (Range::VonNeumann.size(2), Range::Moore.size(2)));
+
+	//A radius 1 square without the middle is Life-like
+	assert_eq!(Ok(Rule::classic()), Rule::parse("R1,C0,M0,S2..3,B3..3,NM"));
+	assert!(Rule::parse("R11,S1..2,B1..2").is_err());
+	assert!(Rule::parse("R0,S1..2,B1..2").is_err());
+	assert!(Rule::parse("R1,S1..2").is_err());
+	assert!(Rule::parse("R1,S1..9,B1..2").is_err());
+	assert!(Rule::parse("R1,S3..2,B1..2").is_err());
+	assert!(Rule::parse("R1,S1..2,B1..2,NX").is_err());
+	assert!(Rule::parse("R1,S1..2,B1..2,M2").is_err());
+	assert!(Rule::parse("R1,S1..2,B1..2,Q").is_err());
+}
+
+#[test]
+fn test_rule_hex() {
+	let hex = Rule::parse("B2/S34H").unwrap();
+	assert_eq!((true, 1, 2), (hex.neighborhood().is_hex(), hex.radius(), hex.states()));
+	assert_eq!((ALIVE, DEAD), (hex.next(DEAD, 2), hex.next(DEAD, 3)));
+	assert_eq!((ALIVE, ALIVE, DEAD), (hex.next(ALIVE, 3), hex.next(ALIVE, 4), hex.next(ALIVE, 6)));
+
+	//The H stays on the end when printed, after any C part
+	assert_eq!("B2/S34H", hex.to_string());
+	assert_eq!(Ok(hex), Rule::parse(" s34/b2h "));
+	assert_eq!("B2/S/C3H", Rule::parse("B2/S/C3H").unwrap().to_string());
+	assert!(!Rule::parse("B2/S34").unwrap().neighborhood().is_hex());
+
+	//Hex cells can't have more than six neighbors
+	assert!(Rule::parse("B2/S36H").is_ok());
+	assert_eq!(Err("'7' is not a neighbor count".to_string()), Rule::parse("B2/S37H"));
+	assert!(Rule::parse("H").is_err());
+}
+
+#[test]
+fn test_rule_neighborhoods() {
+	//A V on the end counts the four cells sharing an edge, the same as a Larger than Life diamond of radius 1
+	let plus = Rule::parse("B1/S34V").unwrap();
+	assert_eq!(&Neighborhood::von_neumann(), plus.neighborhood());
+	assert_eq!("B1/S34V", plus.to_string());
+	assert_eq!(Ok(plus.clone()), Rule::parse("R1,C0,M0,S3..4,B1..1,NN"));
+	assert_eq!(Err("'5' is not a neighbor count".to_string()), Rule::parse("B1/S45V"));
+	assert_eq!(&Neighborhood::range(Range::Moore, 5, true), Rule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap().neighborhood());
+
+	//Other neighborhoods keep the counts they can reach, but have no letter
+	let cross = Rule::classic().with_neighborhood(Neighborhood::cross()).unwrap();
+	assert_eq!((ALIVE, DEAD), (cross.next(DEAD, 3), cross.next(ALIVE, 4)));
+	assert_eq!("B3/S23", cross.to_string());
+	assert_eq!(Ok(plus.clone()), Rule::parse("B1/S348").unwrap().with_neighborhood(Neighborhood::von_neumann()));
+	assert_eq!(Ok(Rule::classic()), cross.with_neighborhood(Neighborhood::moore()));
+	assert!(Rule::parse("R2,S1..2,B1..2").unwrap().with_neighborhood(Neighborhood::cross()).is_err());
+}
+
+#[test]
+fn test_rule_isotropic() {
+	//Every block names the count around its middle, with a letter unless the count is 0 or 8
+	let mut seen = vec![0; 9];
+	for block in (0..BLOCKS).filter(|block| block & MIDDLE == 0) {
+		let (count, letter) = hensel(block);
+		assert_eq!(letter.is_none(), count == 0 || count == 8);
+		seen[count] += 1;
+	}
+	assert_eq!(vec![1, 8, 28, 56, 70, 56, 28, 8, 1], seen);
+	assert_eq!((2, Some('i')), hensel(2 | 128));
+	assert_eq!((2, Some('a')), hensel(2 | 4));
+	assert_eq!((3, Some('y')), hensel(1 | 4 | 128));
+	assert_eq!((4, Some('t')), hensel(1 | 2 | 4 | 128));
+	assert_eq!((5, Some('c')), hensel(RING ^ (1 | 4 | 256)));
+
+	//tlife: Life, except 2 neighbors on opposite sides don't survive and only one arrangement of 4 does
+	let tlife = Rule::parse("B3/S2-i34q").unwrap();
+	assert_eq!("B3/S2-i34q", tlife.to_string());
+	let block = |cells: &[usize]| cells.iter().fold(0, |block, bit| block | 1 << bit);
+	assert_eq!((DEAD, ALIVE), (tlife.next(ALIVE, block(&[1, 4, 7])), tlife.next(ALIVE, block(&[1, 4, 5]))));
+	assert_eq!((ALIVE, DEAD), (tlife.next(ALIVE, block(&[1, 2, 4, 5, 6])), tlife.next(ALIVE, block(&[0, 2, 4, 6, 8]))));
+	assert_eq!((ALIVE, ALIVE), (tlife.next(DEAD, block(&[0, 2, 8])), tlife.next(DEAD, block(&[3, 5, 7]))));
+
+	//Letters may be written in any order and case, and are printed short
+	assert_eq!("B2-a/S12", Rule::parse("B2ceikn/S12").unwrap().to_string());
+	assert_eq!("B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e", Rule::parse("B2ic3ia4c8/S02EA3eijkq4zi5ar6i7e").unwrap().to_string());
+	assert_eq!("B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e/C3", Rule::parse("B2ci3ai4c8/S02ae3eijkq4iz5ar6i7e/C3").unwrap().to_string());
+
+	//Every arrangement of a count is the same as no letters
+	assert_eq!(Ok(Rule::classic()), Rule::parse("B3cekaijnqry/S2-ceaikn23"));
+	assert_eq!(Ok(Rule::classic()), Rule::parse("B3/S2ceaikn3").unwrap().with_neighborhood(Neighborhood::moore()));
+
+	assert_eq!(Err("'t' is not an arrangement of 3 neighbors".to_string()), Rule::parse("B3t/S23"));
+	assert!(Rule::parse("B3-/S23").is_err());
+	assert!(Rule::parse("B9a/S23").is_err());
+	assert!(Rule::parse("B2a/S12V").is_err());
+	assert!(tlife.with_neighborhood(Neighborhood::cross()).is_err());
+}
+
+#[test]
+fn test_rule_wireworld() {
+	let wireworld = Rule::parse(" wireWorld ").unwrap();
+	assert_eq!(Rule::wireworld(), wireworld);
+	assert_eq!(("WireWorld", 4, CONDUCTOR), (wireworld.to_string().as_str(), wireworld.states(), wireworld.painted()));
+	assert_eq!((false, ALIVE), (Rule::classic().is_wireworld(), Rule::classic().painted()));
+
+	//Electrons move on along the wire, and a conductor only sparks with 1 or 2 heads around it
+	assert_eq!((TAIL, CONDUCTOR, DEAD), (wireworld.next(HEAD, 0), wireworld.next(TAIL, 2), wireworld.next(DEAD, 2)));
+	let sparks: Vec<State> = (0..5).map(|heads| wireworld.next(CONDUCTOR, heads)).collect();
+	assert_eq!(vec![CONDUCTOR, HEAD, HEAD, CONDUCTOR, CONDUCTOR], sparks);
+
+	assert_eq!(Ok(wireworld.clone()), wireworld.with_neighborhood(Neighborhood::moore()));
+	assert!(wireworld.with_neighborhood(Neighborhood::hexagonal()).is_err());
+}
+
+#[test]
+fn test_rule_colors() {
+	let immigration = Rule::parse("Immigration").unwrap();
+	let quadlife = Rule::parse("quadlife").unwrap();
+	assert_eq!(("Immigration", 2, "QuadLife", 4), (immigration.to_string().as_str(), immigration.colors(), quadlife.to_string().as_str(), quadlife.colors()));
+	assert_eq!(0, Rule::classic().colors());
+
+	//The colors follow the classic rule
+	assert_eq!((ALIVE, ALIVE, DEAD), (quadlife.next(DEAD, 3), immigration.next(ALIVE, 2), immigration.next(DEAD, 2)));
+
+	//Newborns take the color most of their parents have
+	assert_eq!((0, 1), (immigration.newborn_color(&[2, 1]), immigration.newborn_color(&[0, 3])));
+	assert_eq!((2, 3), (quadlife.newborn_color(&[1, 0, 2, 0]), quadlife.newborn_color(&[0, 0, 0, 3])));
+
+	//Three parents of three colors give the fourth
+	assert_eq!((3, 0, 2), (quadlife.newborn_color(&[1, 1, 1, 0]), quadlife.newborn_color(&[0, 1, 1, 1]), quadlife.newborn_color(&[1, 1, 0, 1])));
+
+	assert_eq!(Ok(quadlife.clone()), quadlife.with_neighborhood(Neighborhood::moore()));
+	assert_eq!(Err("Immigration only counts the eight cells around".to_string()), immigration.with_neighborhood(Neighborhood::von_neumann()));
+}
+
+#[test]
+fn test_rule_one_dimensional() {
+	let w30 = Rule::parse("w30").unwrap();
+	assert_eq!(("W30", Boundary::Dead), (w30.to_string().as_str(), w30.line().map(|line| line.boundary()).unwrap()));
+	assert_eq!("T10R2", Rule::parse("T10R2").unwrap().to_string());
+	assert_eq!(None, Rule::classic().line());
+	assert!(Rule::parse("W256").is_err());
+
+	//Only one dimensional rules take a boundary, and they don't take neighborhoods
+	let wrapped = w30.with_boundary(Boundary::Wrap).unwrap();
+	assert_eq!((Some(Boundary::Wrap), "W30"), (wrapped.line().map(|line| line.boundary()), wrapped.to_string().as_str()));
+	assert!(wrapped != w30);
+	assert!(Rule::classic().with_boundary(Boundary::Wrap).is_err());
+	assert!(w30.with_neighborhood(Neighborhood::moore()).is_err());
+
+	//WireWorld still reads as a name
+	assert_eq!(Ok(Rule::wireworld()), Rule::parse("WireWorld"));
+}
@@ -0,0 +1,286 @@
+// Copyright 2016 Matthew Greenlaw.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Simulation timing. Generations run on a fixed timestep: the time between frames
+// is saved up, and a generation is spent from it each time enough has built up.
+// That keeps the speed of the world independent of the frame rate.
+
+use std::fmt;
+
+///Most generations run in one frame, so a slow world can't stall drawing
+pub const MAX_GENERATIONS_PER_FRAME: u32 = 1000;
+
+///Seconds between updates of the measured speed
+const MEASURE_SECONDS: f64 = 0.5;
+
+///Target speed of the simulation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Speed {
+	///Generations per second, may be below 1
+	Rate(f64),
+	///As many generations as fit in a frame
+	Unlimited,
+}
+
+///Speeds picked by the speed slider and keys, slowest first
+pub const SPEEDS: [Speed; 12] = [
+	Speed::Rate(0.25), Speed::Rate(0.5), Speed::Rate(1.0), Speed::Rate(2.0),
+	Speed::Rate(5.0), Speed::Rate(10.0), Speed::Rate(20.0), Speed::Rate(30.0),
+	Speed::Rate(60.0), Speed::Rate(120.0), Speed::Rate(240.0), Speed::Unlimited,
+];
+
+impl Speed {
+	/// Returns the index in SPEEDS of the fastest preset no faster than this speed
+	pub fn level(&self) -> usize {
+		SPEEDS.iter().rposition(|preset| !preset.faster_than(self)).unwrap_or(0)
+	}
+
+	/// Returns true if this speed is faster than another.
+	fn faster_than(&self, other: &Speed) -> bool {
+		match (*self, *other) {
+			(Speed::Unlimited, Speed::Unlimited) => false,
+			(Speed::Unlimited, _) => true,
+			(_, Speed::Unlimited) => false,
+			(Speed::Rate(a), Speed::Rate(b)) => a > b,
+		}
+	}
+}
+
+impl fmt::Display for Speed {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Speed::Rate(rate) => write!(f, "{} gen/s", rate),
+			Speed::Unlimited => write!(f, "max"),
+		}
+	}
+}
+
+///Decides how many generations to run each frame and measures how fast they really run
+pub struct Clock {
+	speed: Speed,
+	///Seconds saved up towards the next generation
+	accumulator: f64,
+	///Generations due this frame, spent as they are recorded
+	due: u32,
+	///Seconds and generations counted towards the next measurement, and whether any were left unrun
+	measuring: (f64, u32, bool),
+	///Generations per second actually run, as of the last measurement
+	generations_per_second: f64,
+	///True if generations were left unrun as of the last measurement
+	behind: bool,
+	///Seconds between the last two frames
+	frame_time: f64,
+}
+
+impl Clock {
+	/// Generates a clock running at a given speed.
+	///
+	/// # Arguments
+	/// * 'speed' - The target speed.
+	pub fn new(speed: Speed) -> Self {
+		Clock {
+			speed,
+			accumulator: 0.0,
+			due: 0,
+			measuring: (0.0, 0, false),
+			generations_per_second: 0.0,
+			behind: false,
+			frame_time: 0.0,
+		}
+	}
+
+	/// Returns the target speed
+	pub fn speed(&self) -> Speed {
+		self.speed
+	}
+
+	/// Changes the target speed. Time saved up at the old speed is dropped.
+	///
+	/// # Arguments
+	/// * 'speed' - The new target speed.
+	pub fn set_speed(&mut self, speed: Speed) {
+		self.speed = speed;
+		self.accumulator = 0.0;
+	}
+
+	/// Moves to the next faster preset speed
+	pub fn speed_up(&mut self) {
+		let level = (self.speed.level() + 1).min(SPEEDS.len() - 1);
+		self.set_speed(SPEEDS[level]);
+	}
+
+	/// Moves to the next slower preset speed
+	pub fn slow_down(&mut self) {
+		let level = self.speed.level();
+		//A speed between presets slows to the preset below it
+		let level = if SPEEDS[level] == self.speed { level.saturating_sub(1) } else { level };
+		self.set_speed(SPEEDS[level]);
+	}
+
+	/// Saves up the time since the last frame. Only the generations recorded as run are
+	/// spent from it, so generations cut short carry over to the next frame.
+	///
+	/// # Arguments
+	/// * 'seconds' - Time since the last frame.
+	///
+	/// # Return
+	/// * u32 - The number of generations to run this frame.
+	pub fn advance(&mut self, seconds: f64) -> u32 {
+		self.due = match self.speed {
+			Speed::Rate(rate) => {
+				let step = 1.0 / rate;
+				//Too far behind to catch up, the backlog is kept to one frame's worth
+				self.accumulator = (self.accumulator + seconds).min(MAX_GENERATIONS_PER_FRAME as f64 * step);
+				//Allow for rounding so whole steps built from many frames aren't missed
+				(self.accumulator / step + 1e-9).floor() as u32
+			},
+			Speed::Unlimited => MAX_GENERATIONS_PER_FRAME,
+		};
+		self.due
+	}
+
+	/// Records a frame for the measured speed and frame time, and spends the generations run.
+	///
+	/// # Arguments
+	/// * 'seconds'     - Time since the last frame.
+	/// * 'generations' - The number of generations run this frame.
+	pub fn record(&mut self, seconds: f64, generations: u32) {
+		if let Speed::Rate(rate) = self.speed {
+			self.accumulator = (self.accumulator - generations as f64 / rate).max(0.0);
+			self.measuring.2 |= generations < self.due;
+		}
+		self.due = 0;
+		self.frame_time = seconds;
+		self.measuring.0 += seconds;
+		self.measuring.1 += generations;
+		if self.measuring.0 >= MEASURE_SECONDS {
+			self.generations_per_second = self.measuring.1 as f64 / self.measuring.0;
+			self.behind = self.measuring.2;
+			self.measuring = (0.0, 0, false);
+		}
+	}
+
+	/// Returns true if the target speed couldn't be kept up as of the last measurement
+	pub fn behind(&self) -> bool {
+		self.behind
+	}
+
+	/// Returns the generations per second actually run
+	pub fn generations_per_second(&self) -> f64 {
+		self.generations_per_second
+	}
+
+	/// Returns the seconds between the last two frames
+	pub fn frame_time(&self) -> f64 {
+		self.frame_time
+	}
+}
+
+#[test]
+fn test_clock_fixed_timestep() {
+	//Runs every generation due in a frame
+	let frame = |clock: &mut Clock, seconds: f64| {
+		let due = clock.advance(seconds);
+		clock.record(seconds, due);
+		due
+	};
+
+	//Two generations a second, no matter how the frames fall
+	let mut clock = Clock::new(Speed::Rate(2.0));
+	assert_eq!(0, frame(&mut clock, 0.3));
+	assert_eq!(1, frame(&mut clock, 0.3));
+	assert_eq!(2, frame(&mut clock, 1.0));
+	assert_eq!(0, frame(&mut clock, 0.3));
+
+	//Slower than one a second
+	clock.set_speed(Speed::Rate(0.25));
+	let generations: u32 = (0..240).map(|_| frame(&mut clock, 1.0 / 60.0)).sum();
+	assert_eq!(1, generations);
+	let generations: u32 = (0..239).map(|_| frame(&mut clock, 1.0 / 60.0)).sum();
+	assert_eq!(0, generations);
+	assert_eq!(1, frame(&mut clock, 1.0 / 60.0));
+
+	//A long stall doesn't queue up an endless backlog
+	clock.set_speed(Speed::Rate(240.0));
+	assert_eq!(MAX_GENERATIONS_PER_FRAME, frame(&mut clock, 60.0));
+	assert_eq!(0, frame(&mut clock, 0.001));
+
+	clock.set_speed(Speed::Unlimited);
+	assert_eq!(MAX_GENERATIONS_PER_FRAME, frame(&mut clock, 0.0));
+}
+
+#[test]
+fn test_clock_carries_over() {
+	//Generations cut short are still due next frame
+	let mut clock = Clock::new(Speed::Rate(100.0));
+	assert_eq!(10, clock.advance(0.1));
+	clock.record(0.1, 4);
+	assert_eq!(6, clock.advance(0.0));
+	clock.record(0.0, 6);
+	assert_eq!(0, clock.advance(0.0));
+
+	//Falling short during a measurement shows the clock is behind
+	let mut clock = Clock::new(Speed::Rate(100.0));
+	for _ in 0..5 {
+		clock.advance(0.1);
+		clock.record(0.1, 5);
+	}
+	assert!(clock.behind());
+	assert_eq!(50.0, clock.generations_per_second());
+	for _ in 0..5 {
+		let due = clock.advance(0.1);
+		clock.record(0.1, due);
+	}
+	assert!(!clock.behind());
+}
+
+#[test]
+fn test_clock_speed_presets() {
+	let mut clock = Clock::new(Speed::Rate(0.25));
+	clock.slow_down();
+	assert_eq!(Speed::Rate(0.25), clock.speed());
+	clock.speed_up();
+	assert_eq!(Speed::Rate(0.5), clock.speed());
+
+	//Speeds between presets move to the neighbouring presets
+	clock.set_speed(Speed::Rate(7.0));
+	clock.speed_up();
+	assert_eq!(Speed::Rate(10.0), clock.speed());
+	clock.set_speed(Speed::Rate(7.0));
+	clock.slow_down();
+	assert_eq!(Speed::Rate(5.0), clock.speed());
+
+	clock.set_speed(Speed::Rate(240.0));
+	clock.speed_up();
+	clock.speed_up();
+	assert_eq!(Speed::Unlimited, clock.speed());
+	assert_eq!("max", clock.speed().to_string());
+	assert_eq!("0.5 gen/s", Speed::Rate(0.5).to_string());
+}
+
+#[test]
+fn test_clock_measures_speed() {
+	let mut clock = Clock::new(Speed::Rate(8.0));
+	for _ in 0..31 {
+		let generations = clock.advance(1.0 / 64.0);
+		clock.record(1.0 / 64.0, generations);
+	}
+	//Nothing measured until half a second has gone by
+	assert_eq!(0.0, clock.generations_per_second());
+
+	let generations = clock.advance(1.0 / 64.0);
+	clock.record(1.0 / 64.0, generations);
+	assert_eq!(8.0, clock.generations_per_second());
+	assert_eq!(1.0 / 64.0, clock.frame_time());
+}
@@ -12,13 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+// A small widget toolkit. A UiElem is a titled panel that stacks any mix of widgets
+// below its header. Widgets are identified by a client defined id of type C, which
+// they hand back in the events they report.
+
+use std::any::Any;
+use std::collections::VecDeque;
+
 //Import graphics crate
 extern crate ggez;
-use ggez::{Context, graphics};
-use ggez::graphics::{Point2};
+use ggez::event::{Keycode};
 
 //Import global parameters
-use params::{ SIZE_GRID_PIXELS };
+use params::{ SIZE_GRID_PIXELS, WIDTH_UI_INDENT, HEIGHT_UI_LINE };
+
+use render::{Renderer, Color};
+
+const COLOR_TEXT: Color = [0.5, 0.5, 0.5, 1.0];
+const COLOR_TEXT_DARK: Color = [0.1, 0.1, 0.1, 1.0];
+const COLOR_CONTROL: Color = [0.85, 0.85, 0.85, 1.0];
+const COLOR_ERROR: Color = [0.8, 0.1, 0.1, 1.0];
+const COLOR_MARKER: Color = [0.2, 0.4, 0.9, 1.0];
 
 ///Define coordinates
 pub struct Coord { x: i32, y: i32, }
@@ -41,246 +55,1399 @@ impl Clone for Coord {
 	fn clone(&self) -> Coord { Coord::new(self.x, self.y) }
 }
 
-//Defines a base UI element that has a header and text
-pub struct Frame {
+///The space a widget takes up in the window, in grids
+#[derive(Clone)]
+struct Area {
 	coord: Coord,
-	height: i32,
 	width: i32,
+	height: i32,
+}
+
+impl Area {
+	/// Generates an area with its top left corner at a coordinate.
+	fn new(coord: (i32, i32), width: i32, height: i32) -> Self {
+		Area { coord: Coord::from(coord), width, height }
+	}
+
+	/// Returns the top left corner of the area in pixels
+	fn pixels(&self) -> (f32, f32) {
+		let coords = self.coord.get_coords();
+		((coords.0 * SIZE_GRID_PIXELS) as f32, (coords.1 * SIZE_GRID_PIXELS) as f32)
+	}
+
+	/// Determines if this area contains a target coordinate.
+	///
+	/// # Arguments
+	/// * 'x & y'  - The target coordinate in pixels.
+	///
+	/// # Return
+	/// * bool - True if the coordinate is within the bounds of this area, False otherwise.
+	fn contains(&self, x:i32, y:i32) -> bool {
+		let topix = |x:i32| { x * SIZE_GRID_PIXELS };
+		let coords = self.coord.get_coords();
+		x > topix(coords.0) && x < topix(coords.0 + self.width) &&
+			y > topix(coords.1) && y < topix(coords.1 + self.height)
+	}
+}
+
+///Input passed from the game to widgets. Coordinates are in window pixels.
+pub enum UiEvent<'a> {
+	///A mouse button went down
+	Press(i32, i32),
+	///The mouse moved while a button was held down
+	Drag(i32, i32),
+	///A mouse button came back up
+	Click(i32, i32),
+	///The mouse wheel turned at a coordinate, positive away from the user
+	Scroll(i32, i32, i32),
+	///A key was pressed
+	Key(Keycode),
+	///Text was typed
+	Text(&'a str),
+}
+
+///Reported by a widget when the user changes it. C identifies the widget.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WidgetEvent<C> {
+	///A button was clicked
+	Pressed(C),
+	///A toggle or checkbox changed to the given state
+	Toggled(C, bool),
+	///A slider moved to the given value
+	Changed(C, f32),
+	///Text was committed with Enter
+	Committed(C, String),
+	///A point was picked on a minimap, as (x, y) fractions of the world between 0.0 and 1.0
+	Picked(C, f32, f32),
+	///The item at an index was picked from a list
+	Selected(C, usize),
+}
+
+///Anything that can live in a UiElem
+pub trait Widget<C> {
+	/// Returns the id reported in this widget's events.
+	fn id(&self) -> C;
+
+	/// Returns the widget as Any, so UiElem::widget can hand it back as its own type.
+	fn as_any(&mut self) -> &mut dyn Any;
+
+	/// Places the widget in the window.
+	///
+	/// # Arguments
+	/// * 'coord' - The top left (x, y) coordinate the widget may use.
+	/// * 'width' - The width the widget may use.
+	///
+	/// # Return
+	/// * i32 - The height the widget takes up.
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32;
+
+	/// Draws the widget where it was laid out.
+	///
+	/// # Arguments
+	/// * 'renderer' - The backend to draw with.
+	fn draw(&self, renderer: &mut dyn Renderer);
+
+	/// Reacts to user input.
+	///
+	/// # Arguments
+	/// * 'event' - The input.
+	///
+	/// # Return
+	/// * Option<WidgetEvent<C>> - What changed, None if the input didn't change the widget.
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>>;
+
+	/// Replaces the text shown by the widget. Ignored by widgets without text.
+	fn set_text(&mut self, _text: &str) {}
+
+	/// Sets the state of a widget that can be on or off. Ignored by other widgets.
+	fn set_checked(&mut self, _checked: bool) {}
+
+	/// Sets the value of a widget with a range. Ignored by other widgets.
+	fn set_value(&mut self, _value: f32) {}
+
+	/// Sets or clears an error shown by the widget. Ignored by widgets that can't be wrong.
+	fn set_error(&mut self, _error: Option<String>) {}
+
+	/// Returns true if the widget is taking keyboard input.
+	fn focused(&self) -> bool { false }
+}
+
+/// Draws text inside a button shaped box.
+///
+/// # Arguments
+/// * 'renderer' - The backend to draw with.
+/// * 'area'     - The area of the widget.
+/// * 'text'     - The text on the button.
+fn draw_button(renderer: &mut dyn Renderer, area: &Area, text: &str) {
+	let (x, y) = area.pixels();
+	let (width, height) = renderer.text_size(text);
+	let pad = SIZE_GRID_PIXELS as f32;
+
+	renderer.set_color(COLOR_CONTROL);
+	renderer.fill_rect(x, y, width + 2.0 * pad, height);
+	renderer.set_color(COLOR_TEXT_DARK);
+	renderer.text(text, x + pad, y);
+}
+
+///Static text with a header, used to display values
+pub struct Label<C> {
+	id: C,
+	area: Area,
 	header: String,
 	text: String,
 }
 
-impl Frame {
-	/// Generates a new Frame container.
+impl<C> Label<C> {
+	/// Generates a new Label.
 	///
 	/// # Arguments
-	/// * 'coord'  - Parent's (x, y) coordintes. Note: Coordinates are based off the center of the object.
-	/// * 'height' - Height of this container.
-	/// * 'width'  - Width of this container.
-	/// * 'indent' - X offset from parent.
-	/// * 'offset' - Y offset from parent.
-	/// * 'header' - Header text describing children.
-	/// * 'text'   - Data to display.
-	pub fn new(coord: (i32, i32), height: i32, width: i32, indent: i32, offset: i32, header:String, text: &str) -> Self {
-		Frame {
-			coord: Coord::from((coord.0 + indent, coord.1 + offset)),
-			height: height,
-			width: width,
-			header: header,
-			text: text.to_string(),
+	/// * 'id'     - Identifies the label when updating its text.
+	/// * 'header' - Header text describing the value.
+	/// * 'text'   - Value to display.
+	pub fn new(id: C, header: &str, text: &str) -> Self {
+		Label { id, area: Area::new((0, 0), 0, 0), header: header.to_string(), text: text.to_string() }
+	}
+
+	/// Used only in testing.
+	/// Getter for label's text element
+	#[allow(dead_code)]
+	fn get_text(&self) -> String {
+		self.text.clone()
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for Label<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, HEIGHT_UI_LINE);
+		HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let (x, y) = self.area.pixels();
+
+		//Build the string to draw
+		let mut text = self.header.to_string();
+		text.push_str(self.text.as_str());
+
+		renderer.set_color(COLOR_TEXT);
+		renderer.text(text.as_str(), x, y);
+	}
+
+	fn handle_event(&mut self, _event: &UiEvent) -> Option<WidgetEvent<C>> {
+		None
+	}
+
+	fn set_text(&mut self, text: &str) {
+		self.text = text.to_string();
+	}
+}
+
+///A button that reports a press when clicked
+pub struct Button<C> {
+	id: C,
+	area: Area,
+	text: String,
+}
+
+impl<C> Button<C> {
+	/// Generates a new Button.
+	///
+	/// # Arguments
+	/// * 'id'   - Reported when the button is clicked.
+	/// * 'text' - Text on the button.
+	pub fn new(id: C, text: &str) -> Self {
+		Button { id, area: Area::new((0, 0), 0, 0), text: text.to_string() }
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for Button<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, HEIGHT_UI_LINE);
+		HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		draw_button(renderer, &self.area, self.text.as_str());
+	}
+
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		match *event {
+			UiEvent::Click(x, y) if self.area.contains(x, y) => Some(WidgetEvent::Pressed(self.id)),
+			_ => None,
+		}
+	}
+
+	fn set_text(&mut self, text: &str) {
+		self.text = text.to_string();
+	}
+}
+
+///A button that flips between two states, showing different text for each
+pub struct Toggle<C> {
+	id: C,
+	area: Area,
+	///Shown while the toggle is off
+	off_text: String,
+	///Shown while the toggle is on
+	on_text: String,
+	on: bool,
+}
+
+impl<C> Toggle<C> {
+	/// Generates a new Toggle that starts off.
+	///
+	/// # Arguments
+	/// * 'id'       - Reported when the toggle changes.
+	/// * 'off_text' - Text shown while the toggle is off.
+	/// * 'on_text'  - Text shown while the toggle is on.
+	pub fn new(id: C, off_text: &str, on_text: &str) -> Self {
+		Toggle { id, area: Area::new((0, 0), 0, 0), off_text: off_text.to_string(), on_text: on_text.to_string(), on: false }
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for Toggle<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, HEIGHT_UI_LINE);
+		HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let text = if self.on { &self.on_text } else { &self.off_text };
+		draw_button(renderer, &self.area, text.as_str());
+	}
+
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		match *event {
+			UiEvent::Click(x, y) if self.area.contains(x, y) => {
+				self.on = !self.on;
+				Some(WidgetEvent::Toggled(self.id, self.on))
+			},
+			_ => None,
 		}
 	}
-	
-	/// Updates the text of a Frame with new data.
+
+	fn set_checked(&mut self, checked: bool) {
+		self.on = checked;
+	}
+}
+
+///A box that can be ticked, followed by text
+pub struct Checkbox<C> {
+	id: C,
+	area: Area,
+	text: String,
+	checked: bool,
+}
+
+impl<C> Checkbox<C> {
+	/// Generates a new Checkbox.
 	///
 	/// # Arguments
-	/// * 'text'   - New data to display.
-	fn update(&mut self, text:&str) {
+	/// * 'id'      - Reported when the checkbox changes.
+	/// * 'text'    - Text after the box.
+	/// * 'checked' - True if the box starts ticked.
+	pub fn new(id: C, text: &str, checked: bool) -> Self {
+		Checkbox { id, area: Area::new((0, 0), 0, 0), text: text.to_string(), checked }
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for Checkbox<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, HEIGHT_UI_LINE);
+		HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let (x, y) = self.area.pixels();
+		let size = (3 * SIZE_GRID_PIXELS) as f32;
+		let top = y + (HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32 / 2.0 - size / 2.0;
+
+		//Draw the box, filled in when ticked
+		renderer.set_color(COLOR_CONTROL);
+		renderer.fill_rect(x, top, size, size);
+		if self.checked {
+			renderer.set_color(COLOR_TEXT_DARK);
+			renderer.fill_rect(x + 3.0, top + 3.0, size - 6.0, size - 6.0);
+		}
+
+		renderer.set_color(COLOR_TEXT);
+		renderer.text(self.text.as_str(), x + size + SIZE_GRID_PIXELS as f32, y);
+	}
+
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		match *event {
+			UiEvent::Click(x, y) if self.area.contains(x, y) => {
+				self.checked = !self.checked;
+				Some(WidgetEvent::Toggled(self.id, self.checked))
+			},
+			_ => None,
+		}
+	}
+
+	fn set_checked(&mut self, checked: bool) {
+		self.checked = checked;
+	}
+}
+
+///A header and text on the left half of a line, and a track with a draggable knob on the right half
+pub struct Slider<C> {
+	id: C,
+	area: Area,
+	header: String,
+	///Shown after the header, usually the value in words
+	text: String,
+	min: f32,
+	max: f32,
+	value: f32,
+	///True while the mouse is dragging the knob
+	dragging: bool,
+}
+
+impl<C> Slider<C> {
+	/// Generates a new Slider.
+	///
+	/// # Arguments
+	/// * 'id'     - Reported when the slider moves.
+	/// * 'header' - Header text describing the value.
+	/// * 'min'    - The value at the left end of the track.
+	/// * 'max'    - The value at the right end of the track.
+	/// * 'value'  - The starting value.
+	pub fn new(id: C, header: &str, min: f32, max: f32, value: f32) -> Self {
+		Slider {
+			id,
+			area: Area::new((0, 0), 0, 0),
+			header: header.to_string(),
+			text: String::new(),
+			min,
+			max,
+			value,
+			dragging: false,
+		}
+	}
+
+	/// Returns the left and right ends of the track in pixels
+	fn track(&self) -> (f32, f32) {
+		let (x, _) = self.area.pixels();
+		let width = (self.area.width * SIZE_GRID_PIXELS) as f32;
+		(x + width / 2.0, x + width - (2 * SIZE_GRID_PIXELS) as f32)
+	}
+
+	/// Moves the knob to a pixel on the track.
+	///
+	/// # Arguments
+	/// * 'x' - The pixel, clamped to the ends of the track.
+	///
+	/// # Return
+	/// * Option<WidgetEvent<C>> - The new value.
+	fn slide_to(&mut self, x: i32) -> Option<WidgetEvent<C>> where C: Copy {
+		let (left, right) = self.track();
+		let along = ((x as f32 - left) / (right - left)).clamp(0.0, 1.0);
+		self.value = self.min + along * (self.max - self.min);
+		Some(WidgetEvent::Changed(self.id, self.value))
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for Slider<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, HEIGHT_UI_LINE);
+		HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let (x, y) = self.area.pixels();
+		let (left, right) = self.track();
+		let middle = y + (HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32 / 2.0;
+		let knob = left + (self.value - self.min) / (self.max - self.min) * (right - left);
+		let size = (2 * SIZE_GRID_PIXELS) as f32;
+
+		let mut text = self.header.to_string();
+		text.push_str(self.text.as_str());
+		renderer.set_color(COLOR_TEXT);
+		renderer.text(text.as_str(), x, y);
+
+		renderer.set_color(COLOR_CONTROL);
+		renderer.line((left, middle), (right, middle), 4.0);
+		renderer.set_color(COLOR_TEXT_DARK);
+		renderer.fill_rect(knob - size / 4.0, middle - size / 2.0, size / 2.0, size);
+	}
+
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		match *event {
+			UiEvent::Press(x, y) if self.area.contains(x, y) && x as f32 >= self.track().0 - SIZE_GRID_PIXELS as f32 => {
+				self.dragging = true;
+				self.slide_to(x)
+			},
+			UiEvent::Drag(x, _) if self.dragging => self.slide_to(x),
+			UiEvent::Click(_, _) => { self.dragging = false; None },
+			_ => None,
+		}
+	}
+
+	fn set_text(&mut self, text: &str) {
 		self.text = text.to_string();
 	}
 
+	fn set_value(&mut self, value: f32) {
+		self.value = value.max(self.min).min(self.max);
+	}
+}
+
+///A line of text the user can type into, committed with Enter
+pub struct TextInput<C> {
+	id: C,
+	area: Area,
+	header: String,
+	text: String,
+	///The last text committed with Enter, restored if editing is abandoned
+	committed: String,
+	///Position of the caret, counted in characters
+	caret: usize,
+	focused: bool,
+	///Shown after the text when the committed value was rejected
+	error: Option<String>,
+}
+
+impl<C> TextInput<C> {
+	/// Generates a new TextInput.
+	///
+	/// # Arguments
+	/// * 'id'     - Reported when text is committed.
+	/// * 'header' - Header text describing the value.
+	/// * 'text'   - Initial value.
+	pub fn new(id: C, header: &str, text: &str) -> Self {
+		TextInput {
+			id,
+			area: Area::new((0, 0), 0, 0),
+			header: header.to_string(),
+			text: text.to_string(),
+			committed: text.to_string(),
+			caret: text.chars().count(),
+			focused: false,
+			error: None,
+		}
+	}
+
+	/// Gives or takes away keyboard focus. Losing focus abandons uncommitted edits.
+	///
+	/// # Arguments
+	/// * 'focused' - True to give focus.
+	fn focus(&mut self, focused: bool) {
+		if self.focused && !focused {
+			self.text = self.committed.clone();
+		}
+		self.focused = focused;
+		self.caret = self.text.chars().count();
+	}
+
+	/// Byte index of the caret within text
+	fn caret_index(&self) -> usize {
+		self.text.char_indices().nth(self.caret).map_or(self.text.len(), |(i, _)| i)
+	}
+
+	/// Inserts typed text at the caret.
+	///
+	/// # Arguments
+	/// * 'text' - The typed text.
+	fn text_input(&mut self, text: &str) {
+		for c in text.chars().filter(|c| !c.is_control()) {
+			let index = self.caret_index();
+			self.text.insert(index, c);
+			self.caret += 1;
+		}
+	}
+
+	/// Edits the text with a key press:
+	/// * Backspace and Delete remove the character before and after the caret
+	/// * Left, Right, Home and End move the caret
+	/// * Return commits the text and Escape abandons it, both give up focus
+	///
+	/// # Arguments
+	/// * 'key' - The key that was pressed.
+	///
+	/// # Return
+	/// * Option<String> - The committed text if Return was pressed.
+	fn key_press(&mut self, key: Keycode) -> Option<String> {
+		let length = self.text.chars().count();
+		match key {
+			Keycode::Backspace if self.caret > 0 => {
+				self.caret -= 1;
+				let index = self.caret_index();
+				self.text.remove(index);
+			},
+			Keycode::Delete if self.caret < length => {
+				let index = self.caret_index();
+				self.text.remove(index);
+			},
+			Keycode::Left => self.caret = self.caret.saturating_sub(1),
+			Keycode::Right => self.caret = (self.caret + 1).min(length),
+			Keycode::Home => self.caret = 0,
+			Keycode::End => self.caret = length,
+			Keycode::Return | Keycode::KpEnter => {
+				self.committed = self.text.clone();
+				self.focus(false);
+				return Some(self.text.clone());
+			},
+			Keycode::Escape => self.focus(false),
+			_ => (),
+		}
+		None
+	}
+
 	/// Used only in testing.
-	/// Getter for frame's text element
+	/// Getter for the input's text element
 	#[allow(dead_code)]
 	fn get_text(&self) -> String {
 		self.text.clone()
 	}
+}
+
+impl<C: Copy + 'static> Widget<C> for TextInput<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, HEIGHT_UI_LINE);
+		HEIGHT_UI_LINE
+	}
+
+	/// Draws the header, value and any error. A focused input also shows its caret.
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let (mut x, y) = self.area.pixels();
+
+		renderer.set_color(COLOR_TEXT);
+		x += renderer.text(self.header.as_str(), x, y).0;
+
+		//Draw the text in two halves so the caret can go between them
+		let index = self.caret_index();
+		renderer.set_color(COLOR_TEXT_DARK);
+		let (width, height) = renderer.text(&self.text[..index], x, y);
+		x += width;
+		if self.focused {
+			renderer.line((x, y), (x, y + height), 2.0);
+		}
+		x += renderer.text(&self.text[index..], x, y).0;
+
+		if let Some(ref error) = self.error {
+			renderer.set_color(COLOR_ERROR);
+			renderer.text(error.as_str(), x + (2 * SIZE_GRID_PIXELS) as f32, y);
+		}
+	}
+
+	/// Clicking the input focuses it and clicking anywhere else drops focus.
+	/// Keys and text only reach a focused input.
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		match *event {
+			UiEvent::Click(x, y) => { let hit = self.area.contains(x, y); self.focus(hit); None },
+			UiEvent::Key(key) if self.focused => self.key_press(key).map(|text| WidgetEvent::Committed(self.id, text)),
+			UiEvent::Text(text) if self.focused => { self.text_input(text); None },
+			_ => None,
+		}
+	}
+
+	fn set_text(&mut self, text: &str) {
+		self.text = text.to_string();
+		self.committed = text.to_string();
+		self.caret = self.text.chars().count();
+	}
+
+	fn set_error(&mut self, error: Option<String>) {
+		self.error = error;
+	}
+
+	fn focused(&self) -> bool {
+		self.focused
+	}
+}
+
+///Shows the whole world in miniature, with a marker around the part in view.
+///Clicking or dragging on it picks a point in the world.
+pub struct Minimap<C> {
+	id: C,
+	area: Area,
+	///Height of the minimap in lines of ui text
+	lines: i32,
+	///Miniature of the world as (width, height, rgba)
+	image: (u16, u16, Vec<u8>),
+	///Part of the world in view as fractions of the world: (x, y, width, height)
+	marker: (f32, f32, f32, f32),
+	///True while the mouse is dragging over the minimap
+	dragging: bool,
+}
 
-	/// Draws the header and text onto the program context at the designated coordinates. 
+impl<C> Minimap<C> {
+	/// Generates a new Minimap with no picture yet.
 	///
 	/// # Arguments
-	/// * 'ctx' - [ggez global resources](https://docs.rs/ggez/0.3.1/ggez/struct.Context.html). 
-	fn draw(&self, ctx: &mut Context) {
-		let topix = |x:i32| { ((x * SIZE_GRID_PIXELS) as f32) };
-		let coords = self.coord.get_coords();
+	/// * 'id'    - Reported when a point is picked.
+	/// * 'lines' - Height of the minimap in lines of ui text.
+	pub fn new(id: C, lines: i32) -> Self {
+		Minimap {
+			id,
+			area: Area::new((0, 0), 0, 0),
+			lines,
+			image: (0, 0, Vec::new()),
+			marker: (0.0, 0.0, 1.0, 1.0),
+			dragging: false,
+		}
+	}
 
-		//Build the string to draw
-		let mut text = self.header.to_string();
-		text.push_str(self.text.as_str());
+	/// Replaces the picture.
+	///
+	/// # Arguments
+	/// * 'width & height' - The size of the picture in pixels.
+	/// * 'rgba'           - Four bytes of color per pixel, row by row.
+	pub fn set_image(&mut self, width: u16, height: u16, rgba: Vec<u8>) {
+		self.image = (width, height, rgba);
+	}
 
-		//Build the graphical representation of the text using ttf
-		let ttf = &graphics::Font::new(ctx, "/Pacifico.ttf", 24).expect("Missing ttf file");
-		let text = &graphics::Text::new(ctx, text.as_str(), ttf).expect("Error generating text");
+	/// Moves the marker drawn over the picture.
+	///
+	/// # Arguments
+	/// * 'marker' - The marked part of the picture as (x, y, width, height) fractions of it.
+	pub fn set_marker(&mut self, marker: (f32, f32, f32, f32)) {
+		self.marker = marker;
+	}
 
-		//Draw the text
-		graphics::set_color(ctx, [0.5, 0.5, 0.5, 1.0].into()).expect("Error setting color");
-		graphics::draw(ctx, text, graphics::Point2::new(topix(coords.0), topix(coords.1)), 0.0).expect("Error drawing text"); 
+	/// Returns where the picture is drawn, as large as fits the widget without stretching.
+	///
+	/// # Return
+	/// * (f32, f32, f32, f32) - The picture as (x, y, width, height) in pixels.
+	fn picture(&self) -> (f32, f32, f32, f32) {
+		let (x, y) = self.area.pixels();
+		let (width, height) = (self.image.0 as f32, self.image.1 as f32);
+		if width == 0.0 || height == 0.0 {
+			return (x, y, 0.0, 0.0);
+		}
+		let room = ((self.area.width * SIZE_GRID_PIXELS) as f32, (self.area.height * SIZE_GRID_PIXELS) as f32);
+		let scale = (room.0 / width).min(room.1 / height);
+		(x, y, width * scale, height * scale)
 	}
 
-	/// Determines if this element covers an area of coordinates that contain a target coordinate.
-	/// The bounds of this element are determined by its own coordinates, height, and width.
+	/// Picks the point of the world under a pixel.
 	///
 	/// # Arguments
-	/// * 'x & y'  - The target coordinate.
+	/// * 'x & y' - The pixel, clamped to the picture.
+	fn pick(&self, x: i32, y: i32) -> Option<WidgetEvent<C>> where C: Copy {
+		let (left, top, width, height) = self.picture();
+		let along = |value: f32, start: f32, length: f32| { ((value - start) / length).clamp(0.0, 1.0) };
+		Some(WidgetEvent::Picked(self.id, along(x as f32, left, width), along(y as f32, top, height)))
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for Minimap<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, self.lines * HEIGHT_UI_LINE);
+		self.lines * HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let (x, y, width, height) = self.picture();
+		if width == 0.0 {
+			return;
+		}
+
+		renderer.set_color(COLOR_CONTROL);
+		renderer.fill_rect(x, y, width, height);
+		renderer.pixels(x, y, self.image.0, self.image.1, &self.image.2, width / self.image.0 as f32);
+
+		//Outline the part of the marker on the picture, the camera may look past the edges of the world
+		let (mx, my, mw, mh) = self.marker;
+		let (x0, y0) = (x + mx.max(0.0) * width, y + my.max(0.0) * height);
+		let (x1, y1) = (x + (mx + mw).min(1.0) * width, y + (my + mh).min(1.0) * height);
+		if x1 > x0 && y1 > y0 {
+			renderer.set_color(COLOR_MARKER);
+			renderer.line((x0, y0), (x1, y0), 2.0);
+			renderer.line((x1, y0), (x1, y1), 2.0);
+			renderer.line((x1, y1), (x0, y1), 2.0);
+			renderer.line((x0, y1), (x0, y0), 2.0);
+		}
+	}
+
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		let (left, top, width, height) = self.picture();
+		let on_picture = |x: i32, y: i32| {
+			x as f32 >= left && x as f32 <= left + width && y as f32 >= top && y as f32 <= top + height
+		};
+		match *event {
+			UiEvent::Press(x, y) if width > 0.0 && on_picture(x, y) => {
+				self.dragging = true;
+				self.pick(x, y)
+			},
+			UiEvent::Drag(x, y) if self.dragging => self.pick(x, y),
+			UiEvent::Click(_, _) => { self.dragging = false; None },
+			_ => None,
+		}
+	}
+}
+
+///A list of items, one per line, that scrolls with the mouse wheel. Clicking an item picks it.
+pub struct List<C> {
+	id: C,
+	area: Area,
+	///Number of items shown at once
+	lines: i32,
+	items: Vec<String>,
+	///Index of the first item shown
+	scroll: usize,
+	selected: Option<usize>,
+}
+
+impl<C> List<C> {
+	/// Generates a new List with nothing picked.
 	///
-	/// # Return
-	/// * bool - True if the coordinate is within the bounds of this element, False otherwise.
-	fn contains(&mut self, x:i32, y:i32) -> bool {
-		let topix = |x:i32| { ((x * SIZE_GRID_PIXELS) as i32) };
-		let coords = self.coord.get_coords();
-		if x > topix(coords.0) && x < (topix(coords.0 + self.width)) {
-			if y > topix(coords.1) && y < (topix(coords.1 + self.height)) {
-				return true;
+	/// # Arguments
+	/// * 'id'    - Reported when an item is picked.
+	/// * 'lines' - Number of items shown at once.
+	/// * 'items' - The text of each item.
+	pub fn new(id: C, lines: i32, items: Vec<String>) -> Self {
+		List { id, area: Area::new((0, 0), 0, 0), lines, items, scroll: 0, selected: None }
+	}
+
+	/// Replaces the items, scrolling back to the top with nothing picked.
+	///
+	/// # Arguments
+	/// * 'items' - The text of each item.
+	pub fn set_items(&mut self, items: Vec<String>) {
+		self.items = items;
+		self.scroll = 0;
+		self.selected = None;
+	}
+
+	/// Returns the index of the item shown on a pixel, if any.
+	///
+	/// # Arguments
+	/// * 'x & y' - The pixel.
+	fn item_at(&self, x: i32, y: i32) -> Option<usize> {
+		if !self.area.contains(x, y) {
+			return None;
+		}
+		let (_, top) = self.area.pixels();
+		let line = ((y as f32 - top) / (HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32) as usize;
+		Some(self.scroll + line).filter(|index| *index < self.items.len())
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for List<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, self.lines * HEIGHT_UI_LINE);
+		self.lines * HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let (x, y) = self.area.pixels();
+		let line = (HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32;
+		let width = (self.area.width * SIZE_GRID_PIXELS) as f32;
+		let bar = SIZE_GRID_PIXELS as f32;
+
+		if self.items.is_empty() {
+			renderer.set_color(COLOR_TEXT);
+			renderer.text("Nothing to show", x, y);
+			return;
+		}
+
+		let shown = self.items.iter().enumerate().skip(self.scroll).take(self.lines as usize);
+		for (row, (index, item)) in shown.enumerate() {
+			let top = y + row as f32 * line;
+			if self.selected == Some(index) {
+				renderer.set_color(COLOR_CONTROL);
+				renderer.fill_rect(x, top, width - 2.0 * bar, line);
+				renderer.set_color(COLOR_TEXT_DARK);
+			}
+			else {
+				renderer.set_color(COLOR_TEXT);
 			}
+			renderer.text(item.as_str(), x + bar, top);
+		}
+
+		//A scroll bar shows where the shown items sit in a list too long to show at once
+		let count = self.items.len() as f32;
+		if count > self.lines as f32 {
+			let height = self.lines as f32 * line;
+			renderer.set_color(COLOR_CONTROL);
+			renderer.fill_rect(x + width - bar, y, bar, height);
+			renderer.set_color(COLOR_TEXT);
+			renderer.fill_rect(x + width - bar, y + height * self.scroll as f32 / count, bar, height * self.lines as f32 / count);
+		}
+	}
+
+	fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		match *event {
+			UiEvent::Click(x, y) => self.item_at(x, y).map(|index| {
+				self.selected = Some(index);
+				WidgetEvent::Selected(self.id, index)
+			}),
+			UiEvent::Scroll(x, y, amount) if self.area.contains(x, y) => {
+				let last = self.items.len().saturating_sub(self.lines as usize) as i32;
+				self.scroll = (self.scroll as i32 - amount).max(0).min(last) as usize;
+				None
+			},
+			_ => None,
+		}
+	}
+}
+
+///A line chart of the latest samples, scrolling left as new ones come in. The vertical axis
+///grows and shrinks to fit the largest value shown.
+pub struct Chart<C> {
+	id: C,
+	area: Area,
+	///Height of the chart in lines of ui text, including the legend
+	lines: i32,
+	///Name and color of each line plotted
+	series: Vec<(String, Color)>,
+	///One value for each line per sample, oldest first
+	samples: VecDeque<Vec<f64>>,
+	///Most samples shown
+	length: usize,
+}
+
+impl<C> Chart<C> {
+	/// Generates a new Chart with no samples.
+	///
+	/// # Arguments
+	/// * 'id'     - Identifies the chart.
+	/// * 'lines'  - Height of the chart in lines of ui text, including the legend.
+	/// * 'length' - Most samples shown.
+	/// * 'series' - Name and color of each line plotted.
+	pub fn new(id: C, lines: i32, length: usize, series: Vec<(&str, Color)>) -> Self {
+		Chart {
+			id,
+			area: Area::new((0, 0), 0, 0),
+			lines,
+			series: series.into_iter().map(|(name, color)| (name.to_string(), color)).collect(),
+			samples: VecDeque::new(),
+			length: length.max(2),
+		}
+	}
+
+	/// Adds a sample, dropping the oldest if there are too many to show.
+	///
+	/// # Arguments
+	/// * 'values' - One value for each line plotted.
+	pub fn push_sample(&mut self, values: Vec<f64>) {
+		self.samples.push_back(values);
+		while self.samples.len() > self.length {
+			self.samples.pop_front();
+		}
+	}
+
+	/// Removes every sample
+	pub fn clear_samples(&mut self) {
+		self.samples.clear();
+	}
+
+	/// Sets the most samples shown.
+	///
+	/// # Arguments
+	/// * 'length' - The most samples shown, at least two to draw a line between.
+	pub fn set_length(&mut self, length: usize) {
+		self.length = length.max(2);
+		while self.samples.len() > self.length {
+			self.samples.pop_front();
+		}
+	}
+
+	/// Returns the top of the vertical axis: the smallest 1, 2 or 5 times a power of ten
+	/// no less than any value shown.
+	fn top(&self) -> f64 {
+		let largest = self.samples.iter().flat_map(|sample| sample.iter()).cloned().fold(1.0, f64::max);
+		let mut magnitude = 1.0;
+		while magnitude * 10.0 < largest {
+			magnitude *= 10.0;
+		}
+		[1.0, 2.0, 5.0, 10.0].iter().map(|step| step * magnitude).find(|top| *top >= largest).unwrap_or(largest)
+	}
+}
+
+impl<C: Copy + 'static> Widget<C> for Chart<C> {
+	fn id(&self) -> C { self.id }
+
+	fn as_any(&mut self) -> &mut dyn Any { self }
+
+	fn layout(&mut self, coord: (i32, i32), width: i32) -> i32 {
+		self.area = Area::new(coord, width, self.lines * HEIGHT_UI_LINE);
+		self.lines * HEIGHT_UI_LINE
+	}
+
+	fn draw(&self, renderer: &mut dyn Renderer) {
+		let (x, y) = self.area.pixels();
+		let line = (HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32;
+		let gap = SIZE_GRID_PIXELS as f32;
+
+		//Legend, each name in the color of its line
+		let mut left = x;
+		for &(ref name, color) in self.series.iter() {
+			renderer.set_color(color);
+			left += renderer.text(name.as_str(), left, y).0 + 2.0 * gap;
 		}
 
-		false
+		//Plot below the legend, with the top of the axis in its corner
+		let (width, height) = ((self.area.width * SIZE_GRID_PIXELS) as f32 - gap, (self.lines - 1) as f32 * line);
+		let bottom = y + line + height;
+		let top = self.top();
+		renderer.set_color(COLOR_CONTROL);
+		renderer.fill_rect(x, y + line, width, height);
+		renderer.set_color(COLOR_TEXT);
+		renderer.text(top.to_string().as_str(), x + gap, y + line);
+
+		let step = width / (self.length - 1) as f32;
+		let point = |i: usize, value: f64| { (x + i as f32 * step, bottom - height * (value / top) as f32) };
+		for (series, &(_, color)) in self.series.iter().enumerate() {
+			renderer.set_color(color);
+			let values: Vec<f64> = self.samples.iter().map(|sample| sample.get(series).cloned().unwrap_or(0.0)).collect();
+			for (i, pair) in values.windows(2).enumerate() {
+				renderer.line(point(i, pair[0]), point(i + 1, pair[1]), 2.0);
+			}
+		}
 	}
 
-	///Action to perform if user clicks a coordinate in this element.
-	pub fn mouse_click(&mut self) -> &str {
-		if &self.header == "Pause" { self.header = "Start".to_string(); return "Pause"};
-		if &self.header == "Start" { self.header = "Pause".to_string(); return "Start"};
-		&self.header
+	fn handle_event(&mut self, _event: &UiEvent) -> Option<WidgetEvent<C>> {
+		None
 	}
 }
 
-//Define a UI element with a header and a vector of generic children.
-pub struct UiElem<T> {
+//Define a UI element with a header and a vector of widget children.
+pub struct UiElem<C> {
 	coord: Coord,
 	// height: i32,
-	// width: i32,
+	width: i32,
 	header:String,
-	children: Vec<T>,
+	children: Vec<Box<dyn Widget<C>>>,
 }
 
-impl<T> UiElem<T> {
-	/// Generates a new UiElem container.
+impl<C: Copy + PartialEq> UiElem<C> {
+	/// Generates a new UiElem container and stacks its children below the header.
 	///
 	/// # Arguments
 	/// * 'coord'  - This container's (x, y) coordintes. Note: Coordinates are based off the center of the object.
+	/// * 'width'  - Width of this container.
 	/// * 'header' - Text describing children.
-	/// * 'children'   - Generic children that this container manages.
-	pub fn new(coord: (i32, i32), _height: i32, _width: i32, header:String, children: Vec<T>) -> Self {
-		UiElem {
+	/// * 'children'   - Widgets that this container manages.
+	pub fn new(coord: (i32, i32), _height: i32, width: i32, header:String, children: Vec<Box<dyn Widget<C>>>) -> Self {
+		let mut elem = UiElem {
 			coord: Coord::from(coord),
 			// height: height,
-			// width: width,
+			width,
 			header: header,
 			children: children,
+		};
+		elem.layout();
+		elem
+	}
+
+	/// Places each child on its own line below the header, indented from it
+	fn layout(&mut self) {
+		let (x, mut y) = self.coord.get_coords();
+		y += HEIGHT_UI_LINE;
+		for child in self.children.iter_mut() {
+			y += child.layout((x + WIDTH_UI_INDENT, y), self.width - WIDTH_UI_INDENT);
 		}
 	}
-}
 
-///Implementation for UiElem with Frame children
-impl UiElem<Frame> {
-	/// Updates contained Frames with new text attributes.
-	/// struct Game is the client of this function and calls update on all of its elements
-	/// if they need them or not. Wrapping the strings in an Option is the way UiElem 
-	/// decides if a Frame needs to update. I used this approach primarily in development
-	/// because not all UiElems were ready for the complete update chain.
+	/// Sends user input to every child.
 	///
 	/// # Arguments
-	/// * 'text'   - A vector of strings. The index of a string in the vector corelates to the index of the Frame in the children vector.
-	pub fn update(&mut self, text:Option<Vec<String>>) {
-		//Varify that there is something to update with
-		match text {
-			Some(text) => {
-				//For each of the children, update it with the corasponding text. Order is important.
-				for (i, frame) in self.children.iter_mut().enumerate() {
-					frame.update(text[i].as_str());
-				} 
-			},
-			None =>(),
+	/// * 'event' - The input.
+	///
+	/// # Return
+	/// * Option<WidgetEvent<C>> - What changed, None if the input didn't change any child.
+	pub fn handle_event(&mut self, event: &UiEvent) -> Option<WidgetEvent<C>> {
+		//Every child sees the event, a click on one input has to take focus from the others
+		let mut result = None;
+		for child in self.children.iter_mut() {
+			if let Some(event) = child.handle_event(event) {
+				result = result.or(Some(event));
+			}
 		}
+		result
 	}
 
-	/// Used only in testing.
-	/// Returns a reference to the of the UI element Nth child
-	#[allow(dead_code)]
-	pub fn nth_child(&mut self, index: i32) -> &Frame {
-		&self.children[index as usize]
+	/// Returns true if one of the children is taking keyboard input
+	pub fn focused(&self) -> bool {
+		self.children.iter().any(|child| child.focused())
+	}
+
+	/// Replaces the text of the child with a given id.
+	///
+	/// # Arguments
+	/// * 'id'   - The id of the child.
+	/// * 'text' - The new text.
+	pub fn set_text(&mut self, id: C, text: &str) {
+		for child in self.children.iter_mut().filter(|child| child.id() == id) {
+			child.set_text(text);
+		}
 	}
 
-	/// Draws the header and underline, then sends the draw command to children. 
+	/// Sets the state of the child with a given id.
 	///
 	/// # Arguments
-	/// * 'ctx' - [ggez global resources](https://docs.rs/ggez/0.3.1/ggez/struct.Context.html).
-	pub fn draw(&mut self, ctx: &mut Context) {
-		let topix = |x:i32| { ((x * SIZE_GRID_PIXELS) as f32) };
+	/// * 'id'      - The id of the child.
+	/// * 'checked' - The new state.
+	pub fn set_checked(&mut self, id: C, checked: bool) {
+		for child in self.children.iter_mut().filter(|child| child.id() == id) {
+			child.set_checked(checked);
+		}
+	}
+
+	/// Sets the value of the child with a given id.
+	///
+	/// # Arguments
+	/// * 'id'    - The id of the child.
+	/// * 'value' - The new value.
+	pub fn set_value(&mut self, id: C, value: f32) {
+		for child in self.children.iter_mut().filter(|child| child.id() == id) {
+			child.set_value(value);
+		}
+	}
+
+	/// Sets or clears the error shown by the child with a given id.
+	///
+	/// # Arguments
+	/// * 'id'    - The id of the child.
+	/// * 'error' - Description of what is wrong with the value, None if it is valid.
+	pub fn set_error(&mut self, id: C, error: Option<String>) {
+		for child in self.children.iter_mut().filter(|child| child.id() == id) {
+			child.set_error(error.clone());
+		}
+	}
+
+	/// Returns the child with a given id as the type of widget it is, to reach setters only it has.
+	/// A missing child or the wrong type is a mistake in the code, so it panics.
+	///
+	/// # Arguments
+	/// * 'id' - The id of the child.
+	///
+	/// # Return
+	/// * &mut W - The child.
+	pub fn widget<W: Any>(&mut self, id: C) -> &mut W {
+		self.children.iter_mut()
+			.find(|child| child.id() == id)
+			.and_then(|child| child.as_any().downcast_mut::<W>())
+			.expect("Error finding widget")
+	}
+
+	/// Draws the header and underline, then sends the draw command to children.
+	///
+	/// # Arguments
+	/// * 'renderer' - The backend to draw with.
+	pub fn draw(&mut self, renderer: &mut dyn Renderer) {
+		let topix = |x:i32| { (x * SIZE_GRID_PIXELS) as f32 };
 		let coords = self.coord.get_coords();
-		
+
 		//Draw header
-		let ttf = &graphics::Font::new(ctx, "/Pacifico.ttf", 24).expect("Missing ttf file");
-		let text = &graphics::Text::new(ctx, self.header.as_str(), ttf).expect("Error generating text");
-		graphics::set_color(ctx, [0.1, 0.1, 0.1, 1.0].into()).expect("Error setting color");
-		graphics::draw(ctx, text, graphics::Point2::new(topix(coords.0 + 1), topix(coords.1)), 0.0).expect("Error drawing header"); 
+		renderer.set_color(COLOR_TEXT_DARK);
+		let (width, height) = renderer.text(self.header.as_str(), topix(coords.0 + 1), topix(coords.1));
 
 		//Draw underline
-		graphics::set_color(ctx, [0.1, 0.1, 0.1, 0.9].into()).expect("Error setting color"); 
-		graphics::line(
-			ctx,
-			&[
-				Point2::new(topix(coords.0 + 1), topix(coords.1) + text.height() as f32),
-				Point2::new(topix(coords.0 + 1) + text.width() as f32, topix(coords.1) + text.height() as f32),
-			],
+		renderer.set_color([0.1, 0.1, 0.1, 0.9]);
+		renderer.line(
+			(topix(coords.0 + 1), topix(coords.1) + height),
+			(topix(coords.0 + 1) + width, topix(coords.1) + height),
 			4.0
-		).expect("Error generating line");
+		);
 
 		//Send draw command to children
-		for frame in self.children.iter() {
-			frame.draw(ctx);
+		for child in self.children.iter() {
+			child.draw(renderer);
 		}
 	}
+}
 
-	/// Sends the mouse_click to children if they contain the target coordinate
-	///
-	/// # Arguments
-	/// * 'x & y'  - The target coordinate.
-	pub fn mouse_click(&mut self, x:i32, y:i32) -> Option<String> {
-		for frame in self.children.iter_mut() {
-			if frame.contains(x, y) {
-				return Some(frame.mouse_click().to_string());
-			}
-		}
-		None
-	}
+#[test]
+fn test_label_update() {
+	let mut label = Label::new(0, "Label header", "Test text");
+	label.set_text("updated");
+	assert_eq!("updated".to_string(), label.get_text());
 }
 
 #[test]
-fn test_frame_update() {
+fn test_area_contains() {
 	let height = 10;
 	let width = 10;
+	let area = Area::new((0, 0), width, height);
+	for y in 1..height {
+		for x in 1..width {
+			assert!(area.contains(x, y));
+		}
+	}
+	assert!(!area.contains(0, 5));
+	assert!(!area.contains(5, height * SIZE_GRID_PIXELS));
+}
+
+#[test]
+fn test_uielem_layout_and_set_text() {
+	let mut elem: UiElem<u8> = UiElem::new((0, 0), 0, 40, "UiElem Header".to_string(), vec![
+		Box::new(Label::new(0, "First : ", "")),
+		Box::new(Label::new(1, "Second : ", "")),
+	]);
+	elem.set_text(1, "Updated");
 
-	let mut frame = Frame::new((0, 0), height, width, 0, 0, "Frame header".to_string(), "Test text");
-	frame.update("updated");
-	assert_eq!("updated".to_string(), frame.get_text());
+	//Children stack below the header, indented from it
+	let mut renderer = ::render::MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	assert!(renderer.calls().contains(&::render::DrawCall::Text(
+		"Second : Updated".to_string(),
+		(WIDTH_UI_INDENT * SIZE_GRID_PIXELS) as f32,
+		(2 * HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32
+	)));
+	assert_eq!(vec!["UiElem Header".to_string(), "First : ".to_string(), "Second : Updated".to_string()], renderer.texts());
 }
 
 #[test]
-fn test_frame_contains() {
-	let frame_height = 10;
-	let frame_width = 10;
-	let mut frame = Frame::new((0, 0), frame_height, frame_width, 0, 0, "Test header".to_string(), "Test text");
-	for y in 1..frame_height {
-		for x in 1..frame_width {
-			assert!(frame.contains(x, y));
-		}
-	}
+fn test_uielem_mouse_click() {
+	#[derive(Clone, Copy, Debug, PartialEq)]
+	enum Control { Restart, Pause, Grid, Speed }
+
+	let mut elem = UiElem::new((0, 0), 0, WIDTH_UI_INDENT + 40, "Options".to_string(), vec![
+		Box::new(Button::new(Control::Restart, "Restart")),
+		Box::new(Toggle::new(Control::Pause, "Pause", "Start")),
+		Box::new(Checkbox::new(Control::Grid, "Grid lines", false)),
+		Box::new(Slider::new(Control::Speed, "Speed", 0.0, 10.0, 5.0)),
+	]);
+	let line = |n: i32| { (n * HEIGHT_UI_LINE + 1) * SIZE_GRID_PIXELS };
+
+	//Each widget reports a typed event when clicked
+	assert_eq!(Some(WidgetEvent::Pressed(Control::Restart)), elem.handle_event(&UiEvent::Click(30, line(1))));
+	assert_eq!(Some(WidgetEvent::Toggled(Control::Pause, true)), elem.handle_event(&UiEvent::Click(30, line(2))));
+	assert_eq!(Some(WidgetEvent::Toggled(Control::Pause, false)), elem.handle_event(&UiEvent::Click(30, line(2))));
+	assert_eq!(Some(WidgetEvent::Toggled(Control::Grid, true)), elem.handle_event(&UiEvent::Click(30, line(3))));
+	assert_eq!(None, elem.handle_event(&UiEvent::Click(30, line(6))));
+
+	//Sliders follow a drag along their track, which fills the right half of the line
+	let left = (WIDTH_UI_INDENT + 20) * SIZE_GRID_PIXELS;
+	let right = (WIDTH_UI_INDENT + 40) * SIZE_GRID_PIXELS - 2 * SIZE_GRID_PIXELS;
+	assert_eq!(Some(WidgetEvent::Changed(Control::Speed, 0.0)), elem.handle_event(&UiEvent::Press(left, line(4))));
+	assert_eq!(Some(WidgetEvent::Changed(Control::Speed, 5.0)), elem.handle_event(&UiEvent::Drag((left + right) / 2, 0)));
+	assert_eq!(Some(WidgetEvent::Changed(Control::Speed, 10.0)), elem.handle_event(&UiEvent::Drag(right + 100, 0)));
+	assert_eq!(None, elem.handle_event(&UiEvent::Click(right, line(4))));
+	assert_eq!(None, elem.handle_event(&UiEvent::Drag(left, 0)));
 }
 
 #[test]
-fn test_uielem_update() {
-	let height = 10;
-	let width = 10;
+fn test_uielem_draw() {
+	use render::{MemoryRenderer, DrawCall};
+
+	let mut elem = UiElem::new((0, 0), 0, 40, "Stats".to_string(), vec![
+		Box::new(Label::new(0, "Living : ", "42")),
+	]);
+
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
 
-	let frame = Frame::new((0, 0), height, width, 0, 0, "Frame header".to_string(), "Test text");
-	let mut elem = UiElem::new((0,0), height, width, "UiElem Header".to_string(), vec![frame]);
-	elem.update(Some(vec!["Updated".to_string()]));
-	assert_eq!("Updated".to_string(), elem.nth_child(0).get_text());
+	//Header, then its underline, then the label's header and text as one string
+	let topix = |x:i32| { (x * SIZE_GRID_PIXELS) as f32 };
+	assert_eq!(vec!["Stats".to_string(), "Living : 42".to_string()], renderer.texts());
+	assert!(renderer.calls().contains(&DrawCall::Text("Living : 42".to_string(), topix(WIDTH_UI_INDENT), topix(HEIGHT_UI_LINE))));
+	assert!(renderer.calls().iter().any(|call| match *call {
+		DrawCall::Line(from, to, _) => from.1 == to.1 && to.0 > from.0,
+		_ => false,
+	}));
 }
 
 #[test]
-fn test_uielem_mouse_click() {
-	let height = 10;
-	let width = 10;
+fn test_text_input_editing() {
+	let mut input = TextInput::new(0, "Rule : ", "B3/S23");
 
-	let frame = Frame::new((0, 0), height, width, 0, 0, "Frame header".to_string(), "Test text");
-	let mut elem = UiElem::new((0,0), height, width, "UiElem Header".to_string(), vec![frame]);
+	//Typing goes in at the caret, which starts at the end
+	input.focus(true);
+	input.text_input("6");
+	assert_eq!("B3/S236", input.get_text());
+	input.key_press(Keycode::Backspace);
+	input.key_press(Keycode::Home);
+	input.key_press(Keycode::Right);
+	input.key_press(Keycode::Right);
+	input.text_input("6");
+	input.key_press(Keycode::Delete);
+	assert_eq!("B36S23", input.get_text());
 
-	for y in 1..height {
-		for x in 1..width {
-			assert_eq!(Some("Frame header".to_string()), elem.mouse_click(x, y));
-		}
+	//Losing focus abandons the edit, Return commits it
+	input.focus(false);
+	assert_eq!("B3/S23", input.get_text());
+	input.focus(true);
+	input.key_press(Keycode::Left);
+	input.key_press(Keycode::Left);
+	input.text_input("\t4");
+	assert_eq!(None, input.key_press(Keycode::Left));
+	assert_eq!(Some("B3/S423".to_string()), input.key_press(Keycode::Return));
+	assert!(!input.focused);
+	input.focus(true);
+	input.focus(false);
+	assert_eq!("B3/S423", input.get_text());
+}
+
+#[test]
+fn test_uielem_text_input() {
+	use render::{MemoryRenderer, DrawCall};
+
+	let mut elem = UiElem::new((0, 0), 0, 40, "Advanced".to_string(), vec![
+		Box::new(TextInput::new("Seed", "Seed : ", "")),
+		Box::new(TextInput::new("Size", "Size : ", "100x100")),
+	]);
+	let line = |n: i32| { (n * HEIGHT_UI_LINE + 1) * SIZE_GRID_PIXELS };
+
+	//Clicking an input focuses it, clicking elsewhere drops focus
+	assert_eq!(None, elem.handle_event(&UiEvent::Click(30, line(2))));
+	assert!(elem.focused());
+	elem.handle_event(&UiEvent::Text("0"));
+	assert_eq!(Some(WidgetEvent::Committed("Size", "100x1000".to_string())), elem.handle_event(&UiEvent::Key(Keycode::Return)));
+	assert!(!elem.focused());
+	elem.handle_event(&UiEvent::Click(30, line(1)));
+	elem.handle_event(&UiEvent::Text("1"));
+	elem.handle_event(&UiEvent::Click(500, 500));
+	assert!(!elem.focused());
+
+	//Errors are drawn inline after the value, in red
+	elem.set_error("Size", Some("too big".to_string()));
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	let calls = renderer.calls();
+	let error = calls.iter().position(|call| match *call {
+		DrawCall::Text(ref text, _, _) => text == "too big",
+		_ => false,
+	}).expect("Error is drawn");
+	assert_eq!(DrawCall::SetColor(COLOR_ERROR), calls[error - 1]);
+	assert!(!renderer.texts().contains(&"1".to_string()));
+}
+
+#[test]
+fn test_minimap() {
+	use render::{MemoryRenderer, DrawCall};
+
+	let mut elem = UiElem::new((0, 0), 0, WIDTH_UI_INDENT + 40, "Minimap".to_string(), vec![
+		Box::new(Minimap::new(0, 4)),
+	]);
+
+	//Nothing to pick or draw until there is a picture
+	assert_eq!(None, elem.handle_event(&UiEvent::Press(50, 50)));
+
+	//A 4x2 picture is scaled to fill the width of the widget
+	elem.widget::<Minimap<i32>>(0).set_image(4, 2, vec![0; 4 * 4 * 2]);
+	elem.widget::<Minimap<i32>>(0).set_marker((0.5, -0.5, 1.0, 1.0));
+	let left = (WIDTH_UI_INDENT * SIZE_GRID_PIXELS) as f32;
+	let top = (HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32;
+	let scale = (40 * SIZE_GRID_PIXELS) as f32 / 4.0;
+
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	assert!(renderer.calls().contains(&DrawCall::Pixels(left, top, 4, 2, vec![0; 4 * 4 * 2], scale)));
+	//The marker is cut to the picture
+	assert!(renderer.calls().contains(&DrawCall::Line((left + 2.0 * scale, top), (left + 4.0 * scale, top), 2.0)));
+
+	//Pressing and dragging pick points in the world
+	let (x, y) = ((left + scale) as i32, (top + scale) as i32);
+	assert_eq!(Some(WidgetEvent::Picked(0, 0.25, 0.5)), elem.handle_event(&UiEvent::Press(x, y)));
+	assert_eq!(Some(WidgetEvent::Picked(0, 0.0, 1.0)), elem.handle_event(&UiEvent::Drag(0, 1000)));
+	assert_eq!(None, elem.handle_event(&UiEvent::Click(0, 1000)));
+	assert_eq!(None, elem.handle_event(&UiEvent::Drag(x, y)));
+}
+
+#[test]
+#[should_panic(expected = "Error finding widget")]
+fn test_widget_type() {
+	//Asking for a child as the wrong type of widget is caught, rather than ignored
+	let mut elem = UiElem::new((0, 0), 0, 40, "Minimap".to_string(), vec![
+		Box::new(Minimap::new(0, 4)),
+	]);
+	elem.widget::<Label<i32>>(0);
+}
+
+#[test]
+fn test_list() {
+	use render::{MemoryRenderer};
+
+	let mut elem = UiElem::new((0, 0), 0, 40, "Patterns".to_string(), vec![
+		Box::new(List::new(0, 2, Vec::new())),
+	]);
+	elem.widget::<List<i32>>(0).set_items(vec!["Acorn".to_string(), "Glider".to_string(), "Pulsar".to_string()]);
+	let line = |n: i32| { (n * HEIGHT_UI_LINE + 1) * SIZE_GRID_PIXELS };
+
+	//Clicking an item picks it, and clicks past the end of the list pick nothing
+	assert_eq!(Some(WidgetEvent::Selected(0, 1)), elem.handle_event(&UiEvent::Click(30, line(2))));
+	assert_eq!(None, elem.handle_event(&UiEvent::Click(30, line(3))));
+
+	//Scrolling towards the user moves down the list, but never past the end
+	assert_eq!(None, elem.handle_event(&UiEvent::Scroll(30, line(1), -5)));
+	assert_eq!(Some(WidgetEvent::Selected(0, 2)), elem.handle_event(&UiEvent::Click(30, line(2))));
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	assert_eq!(vec!["Patterns".to_string(), "Glider".to_string(), "Pulsar".to_string()], renderer.texts());
+
+	//Scrolling outside of the list does nothing
+	elem.handle_event(&UiEvent::Scroll(30, line(5), 5));
+	assert_eq!(Some(WidgetEvent::Selected(0, 1)), elem.handle_event(&UiEvent::Click(30, line(1))));
+}
+
+#[test]
+fn test_chart() {
+	use render::{MemoryRenderer, DrawCall};
+
+	let mut elem = UiElem::new((0, 0), 0, WIDTH_UI_INDENT + 41, "Stats".to_string(), vec![
+		Box::new(Chart::new(0, 3, 3, vec![("Living", [0.0, 0.0, 1.0, 1.0]), ("Births", [0.0, 1.0, 0.0, 1.0])])),
+	]);
+	let lines = |renderer: &MemoryRenderer| {
+		renderer.calls().iter().filter(|call| match **call { DrawCall::Line(_, _, width) => width == 2.0, _ => false }).count()
+	};
+
+	//Nothing plotted until there are two samples to join
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	assert_eq!(0, lines(&renderer));
+	assert_eq!(vec!["Stats", "Living", "Births", "1"], renderer.texts());
+
+	//Only the latest samples are kept, and the axis rounds up from the largest of them
+	for values in [vec![900.0, 0.0], vec![10.0, 4.0], vec![5.0, 6.0], vec![15.0, 1.0]] {
+		elem.widget::<Chart<i32>>(0).push_sample(values);
 	}
-}
\ No newline at end of file
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	assert_eq!(2 * 2, lines(&renderer));
+	assert_eq!("20", renderer.texts()[3]);
+
+	//The newest sample sits on the right edge, scaled against the top of the axis
+	let left = (WIDTH_UI_INDENT * SIZE_GRID_PIXELS) as f32;
+	let bottom = (3 * HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32 + (HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32;
+	let height = (2 * HEIGHT_UI_LINE * SIZE_GRID_PIXELS) as f32;
+	let right = left + (40 * SIZE_GRID_PIXELS) as f32;
+	let middle = (left + right) / 2.0;
+	assert!(renderer.calls().contains(&DrawCall::Line((middle, bottom - height * 0.25), (right, bottom - height * 0.75), 2.0)));
+
+	elem.widget::<Chart<i32>>(0).set_length(2);
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	assert_eq!(2, lines(&renderer));
+	assert_eq!("20", renderer.texts()[3]);
+
+	elem.widget::<Chart<i32>>(0).clear_samples();
+	let mut renderer = MemoryRenderer::new();
+	elem.draw(&mut renderer);
+	assert_eq!(0, lines(&renderer));
+}